@@ -0,0 +1,359 @@
+//! Block announcement validation for BABE.
+//!
+//! Cumulus only ships validators that either trust the announcing collator or require a seconded
+//! statement from the relay chain. Neither fits a parachain that authors blocks with BABE, so the
+//! validator in here checks that an announced header carries a BABE pre-digest and seal of an
+//! authority of the epoch the block belongs to. Announcements from anyone else are dropped before
+//! the block is requested or imported.
+
+use std::{error::Error, future::Future, pin::Pin, sync::Arc};
+
+use futures::FutureExt;
+use parachain_template_runtime::opaque::{Block, Header};
+use sc_consensus_babe::{find_pre_digest, BabeWorkerHandle};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::block_validation::{BlockAnnounceValidator, Validation};
+use sp_consensus_babe::{
+    digests::CompatibleDigestItem, AuthorityPair, BabeApi, Epoch, Slot, SlotDuration,
+};
+use sp_core::Pair;
+use sp_runtime::traits::Header as HeaderT;
+
+/// Log target of this module.
+const LOG_TARGET: &str = "babe-announce";
+
+/// Number of slots an announced block may be ahead of our clock.
+const MAX_SLOT_DRIFT: u64 = 2;
+
+/// Reasons for rejecting an announced header.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AnnounceError {
+    /// The header has no BABE pre-digest.
+    MissingPreDigest,
+    /// The header has no BABE seal.
+    MissingSeal,
+    /// The pre-digest refers to an authority index outside the epoch authority set.
+    UnknownAuthority(u32),
+    /// The seal was not produced by the claimed authority.
+    BadSignature,
+    /// The claimed slot is too far in the future.
+    FutureSlot(Slot),
+    /// The claimed slot is not part of the epoch the header was checked against.
+    SlotOutsideEpoch(Slot),
+}
+
+impl AnnounceError {
+    /// Whether the announcing peer should be disconnected.
+    ///
+    /// Only errors that an honest node cannot produce lead to a disconnect. A block for a slot
+    /// we can't judge yet may just be the result of clock drift or of us lagging behind.
+    fn is_misbehavior(&self) -> bool {
+        !matches!(
+            self,
+            AnnounceError::FutureSlot(_) | AnnounceError::SlotOutsideEpoch(_)
+        )
+    }
+}
+
+/// Check that `header` was sealed by an authority of `epoch` for a slot not after `slot_now`
+/// (modulo [`MAX_SLOT_DRIFT`]).
+///
+/// The check doesn't verify the claim to the slot: neither the VRF output of a primary slot, nor
+/// that a secondary slot was assigned to the author. Any authority can announce a header for any
+/// slot of the epoch and pass. Such a block is rejected when it is imported, whose verifier checks
+/// the claim.
+pub fn check_header(header: &Header, epoch: &Epoch, slot_now: Slot) -> Result<(), AnnounceError> {
+    let pre_digest =
+        find_pre_digest::<Block>(header).map_err(|_| AnnounceError::MissingPreDigest)?;
+    let slot = pre_digest.slot();
+
+    if slot > slot_now + MAX_SLOT_DRIFT {
+        return Err(AnnounceError::FutureSlot(slot));
+    }
+
+    if slot < epoch.start_slot || slot >= epoch.start_slot + epoch.duration {
+        return Err(AnnounceError::SlotOutsideEpoch(slot));
+    }
+
+    let authority_index = pre_digest.authority_index();
+    let (author, _) = epoch
+        .authorities
+        .get(authority_index as usize)
+        .ok_or(AnnounceError::UnknownAuthority(authority_index))?;
+
+    let mut header = header.clone();
+    let seal = header
+        .digest_mut()
+        .pop()
+        .ok_or(AnnounceError::MissingSeal)?;
+    let signature = seal.as_babe_seal().ok_or(AnnounceError::MissingSeal)?;
+    let pre_hash = header.hash();
+
+    if !AuthorityPair::verify(&signature, pre_hash, author) {
+        return Err(AnnounceError::BadSignature);
+    }
+
+    Ok(())
+}
+
+/// A [`BlockAnnounceValidator`] that only accepts headers authored by a BABE authority.
+pub struct BabeBlockAnnounceValidator<C> {
+    client: Arc<C>,
+    babe_worker_handle: BabeWorkerHandle<Block>,
+    slot_duration: SlotDuration,
+}
+
+impl<C> BabeBlockAnnounceValidator<C> {
+    /// Create a new instance of the validator.
+    pub fn new(
+        client: Arc<C>,
+        babe_worker_handle: BabeWorkerHandle<Block>,
+        slot_duration: SlotDuration,
+    ) -> Self {
+        Self {
+            client,
+            babe_worker_handle,
+            slot_duration,
+        }
+    }
+}
+
+/// Find the epoch an announced header has to be checked against.
+///
+/// If we know the parent of the header, the BABE worker can tell us the exact epoch. Otherwise we
+/// fall back to the current and next epoch as seen from our best block. Headers beyond that can't
+/// be checked, but such blocks are fetched by the major sync anyway.
+async fn epoch_for_header<C>(
+    client: &C,
+    babe_worker_handle: &BabeWorkerHandle<Block>,
+    header: &Header,
+    slot: Slot,
+) -> Option<Epoch>
+where
+    C: HeaderBackend<Block> + ProvideRuntimeApi<Block>,
+    C::Api: BabeApi<Block>,
+{
+    let parent_hash = *header.parent_hash();
+    if client.header(parent_hash).ok().flatten().is_some() {
+        return babe_worker_handle
+            .epoch_data_for_child_of(parent_hash, header.number().saturating_sub(1), slot)
+            .await
+            .ok()
+            .map(|epoch| (*epoch).clone());
+    }
+
+    let best_hash = client.info().best_hash;
+    let runtime_api = client.runtime_api();
+    [
+        runtime_api.current_epoch(best_hash).ok(),
+        runtime_api.next_epoch(best_hash).ok(),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|epoch| slot >= epoch.start_slot && slot < epoch.start_slot + epoch.duration)
+}
+
+impl<C> BlockAnnounceValidator<Block> for BabeBlockAnnounceValidator<C>
+where
+    C: HeaderBackend<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+    C::Api: BabeApi<Block>,
+{
+    fn validate(
+        &mut self,
+        header: &Header,
+        _data: &[u8],
+    ) -> Pin<Box<dyn Future<Output = Result<Validation, Box<dyn Error + Send>>> + Send>> {
+        let client = self.client.clone();
+        let babe_worker_handle = self.babe_worker_handle.clone();
+        let slot_now = Slot::from_timestamp(sp_timestamp::Timestamp::current(), self.slot_duration);
+        let header = header.clone();
+
+        async move {
+            let slot = match find_pre_digest::<Block>(&header) {
+                Ok(pre_digest) => pre_digest.slot(),
+                Err(_) => {
+                    log::debug!(
+                        target: LOG_TARGET,
+                        "Rejecting announcement of {:?} without BABE pre-digest",
+                        header.hash(),
+                    );
+                    return Ok(Validation::Failure { disconnect: true });
+                }
+            };
+
+            let Some(epoch) = epoch_for_header(&*client, &babe_worker_handle, &header, slot).await
+            else {
+                log::debug!(
+                    target: LOG_TARGET,
+                    "Ignoring announcement of {:?}, no known epoch for slot {}",
+                    header.hash(),
+                    slot,
+                );
+                return Ok(Validation::Failure { disconnect: false });
+            };
+
+            match check_header(&header, &epoch, slot_now) {
+                Ok(()) => Ok(Validation::Success { is_new_best: false }),
+                Err(err) => {
+                    log::debug!(
+                        target: LOG_TARGET,
+                        "Rejecting announcement of {:?}: {:?}",
+                        header.hash(),
+                        err,
+                    );
+                    Ok(Validation::Failure {
+                        disconnect: err.is_misbehavior(),
+                    })
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_consensus_babe::{
+        digests::{PreDigest, SecondaryPlainPreDigest},
+        AllowedSlots, BabeEpochConfiguration,
+    };
+    use sp_runtime::{traits::Hash as HashT, Digest, DigestItem};
+
+    fn authority(seed: &str) -> AuthorityPair {
+        AuthorityPair::from_string(&format!("//{}", seed), None).expect("static values are valid")
+    }
+
+    fn epoch(authorities: &[&AuthorityPair]) -> Epoch {
+        Epoch {
+            epoch_index: 1,
+            start_slot: 100.into(),
+            duration: 10,
+            authorities: authorities.iter().map(|p| (p.public(), 1)).collect(),
+            randomness: [0; 32],
+            config: BabeEpochConfiguration {
+                c: (1, 4),
+                allowed_slots: AllowedSlots::PrimaryAndSecondaryPlainSlots,
+            },
+        }
+    }
+
+    fn unsealed_header(authority_index: u32, slot: u64) -> Header {
+        let pre_digest = PreDigest::SecondaryPlain(SecondaryPlainPreDigest {
+            authority_index,
+            slot: slot.into(),
+        });
+        Header::new(
+            1,
+            Default::default(),
+            Default::default(),
+            sp_runtime::traits::BlakeTwo256::hash(b"parent"),
+            Digest {
+                logs: vec![DigestItem::babe_pre_digest(pre_digest)],
+            },
+        )
+    }
+
+    fn sealed_header(authority_index: u32, slot: u64, signer: &AuthorityPair) -> Header {
+        let mut header = unsealed_header(authority_index, slot);
+        let signature = signer.sign(header.hash().as_ref());
+        header.digest_mut().push(DigestItem::babe_seal(signature));
+        header
+    }
+
+    #[test]
+    fn accepts_header_sealed_by_authority() {
+        let (alice, bob) = (authority("Alice"), authority("Bob"));
+        let epoch = epoch(&[&alice, &bob]);
+
+        assert_eq!(
+            check_header(&sealed_header(1, 105, &bob), &epoch, 105.into()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn rejects_header_without_pre_digest() {
+        let alice = authority("Alice");
+        let header = Header::new(
+            1,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        );
+
+        assert_eq!(
+            check_header(&header, &epoch(&[&alice]), 105.into()),
+            Err(AnnounceError::MissingPreDigest)
+        );
+    }
+
+    #[test]
+    fn rejects_unsealed_header() {
+        let alice = authority("Alice");
+
+        assert_eq!(
+            check_header(&unsealed_header(0, 105), &epoch(&[&alice]), 105.into()),
+            Err(AnnounceError::MissingSeal)
+        );
+    }
+
+    #[test]
+    fn rejects_header_sealed_by_non_authority() {
+        let (alice, mallory) = (authority("Alice"), authority("Mallory"));
+        let header = sealed_header(0, 105, &mallory);
+
+        assert_eq!(
+            check_header(&header, &epoch(&[&alice]), 105.into()),
+            Err(AnnounceError::BadSignature)
+        );
+        assert!(AnnounceError::BadSignature.is_misbehavior());
+    }
+
+    #[test]
+    fn rejects_unknown_authority_index() {
+        let (alice, mallory) = (authority("Alice"), authority("Mallory"));
+        let header = sealed_header(7, 105, &mallory);
+
+        assert_eq!(
+            check_header(&header, &epoch(&[&alice]), 105.into()),
+            Err(AnnounceError::UnknownAuthority(7))
+        );
+    }
+
+    #[test]
+    fn rejects_spam_for_future_slots_without_disconnect() {
+        let alice = authority("Alice");
+        let epoch = epoch(&[&alice]);
+
+        let err = check_header(&sealed_header(0, 108, &alice), &epoch, 105.into()).unwrap_err();
+        assert_eq!(err, AnnounceError::FutureSlot(108.into()));
+        assert!(!err.is_misbehavior());
+    }
+
+    #[test]
+    fn rejects_slot_outside_of_epoch() {
+        let alice = authority("Alice");
+        let epoch = epoch(&[&alice]);
+
+        assert_eq!(
+            check_header(&sealed_header(0, 95, &alice), &epoch, 105.into()),
+            Err(AnnounceError::SlotOutsideEpoch(95.into()))
+        );
+    }
+
+    #[test]
+    fn rejects_every_header_of_a_spamming_peer() {
+        let (alice, mallory) = (authority("Alice"), authority("Mallory"));
+        let epoch = epoch(&[&alice]);
+
+        let rejected = (100..110)
+            .map(|slot| sealed_header(0, slot, &mallory))
+            .filter(|header| check_header(header, &epoch, 110.into()).is_err())
+            .count();
+
+        assert_eq!(rejected, 10);
+    }
+}
@@ -1,24 +1,16 @@
-use cumulus_primitives_core::ParaId;
-use parachain_template_runtime::{AccountId, BabeId, GrandpaId, Signature, EXISTENTIAL_DEPOSIT};
+use codec::{Decode, Encode};
+use cumulus_client_service::storage_proof_size::HostFunctions as ReclaimHostFunctions;
+use parachain_template_runtime::genesis_config_presets;
 use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
+use sc_executor::WasmExecutor;
 use sc_service::ChainType;
 use serde::{Deserialize, Serialize};
-use sp_core::{sr25519, Pair, Public};
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_core::traits::{CallContext, CodeExecutor, RuntimeCode, WrappedRuntimeCode};
+use sp_state_machine::BasicExternalities;
 
 /// Specialized `ChainSpec` for the normal parachain runtime.
 pub type ChainSpec = sc_service::GenericChainSpec<(), Extensions>;
 
-/// The default XCM version to set in genesis config.
-const SAFE_XCM_VERSION: u32 = xcm::prelude::XCM_VERSION;
-
-/// Helper function to generate a crypto pair from seed
-pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
-    TPublic::Pair::from_string(&format!("//{}", seed), None)
-        .expect("static values are valid; qed")
-        .public()
-}
-
 /// The extensions for the [`ChainSpec`].
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ChainSpecGroup, ChainSpecExtension)]
 #[serde(deny_unknown_fields)]
@@ -36,174 +28,128 @@ impl Extensions {
     }
 }
 
-type AccountPublic = <Signature as Verify>::Signer;
-
-/// Generate collator keys from seed.
-///
-/// This function's return type must always match the session keys of the chain in tuple format.
-pub fn get_collator_keys_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
-    get_from_seed::<TPublic>(seed)
+/// The token properties shared by all our chain specs.
+fn properties() -> sc_chain_spec::Properties {
+    // Give your base currency a unit name and decimal places
+    let mut properties = sc_chain_spec::Properties::new();
+    properties.insert("tokenSymbol".into(), "UNIT".into());
+    properties.insert("tokenDecimals".into(), 12.into());
+    properties.insert("ss58Format".into(), 42.into());
+    properties
 }
 
-/// Helper function to generate an account ID from seed
-pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId
-where
-    AccountPublic: From<<TPublic::Pair as Pair>::Public>,
-{
-    AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
+/// The WASM binary of the runtime the chain specs are built for.
+fn wasm_binary() -> &'static [u8] {
+    parachain_template_runtime::WASM_BINARY.expect("WASM binary was not built, please build it!")
 }
 
-/// Generate the session keys from individual elements.
-///
-/// The input must be a tuple of individual keys (a single arg for now since we have just one key).
-pub fn template_session_keys(
-    babe: BabeId,
-    grandpa: GrandpaId,
-) -> parachain_template_runtime::SessionKeys {
-    parachain_template_runtime::SessionKeys { babe, grandpa }
+/// Call the runtime API `method` of the WASM runtime, without any state.
+fn call_runtime<R: Decode>(method: &str, data: &[u8]) -> Result<R, String> {
+    let code = wasm_binary();
+    let executor =
+        WasmExecutor::<(sp_io::SubstrateHostFunctions, ReclaimHostFunctions)>::builder().build();
+    let runtime_code = RuntimeCode {
+        code_fetcher: &WrappedRuntimeCode(code.into()),
+        heap_pages: None,
+        hash: sp_core::blake2_256(code).to_vec(),
+    };
+
+    let result = executor
+        .call(
+            &mut BasicExternalities::new_empty(),
+            &runtime_code,
+            method,
+            data,
+            CallContext::Offchain,
+        )
+        .0
+        .map_err(|e| format!("Calling `{}` failed: {}", method, e))?;
+    R::decode(&mut &result[..]).map_err(|e| format!("Invalid result of `{}`: {}", method, e))
 }
 
-pub fn development_config() -> ChainSpec {
-    // Give your base currency a unit name and decimal places
-    let mut properties = sc_chain_spec::Properties::new();
-    properties.insert("tokenSymbol".into(), "UNIT".into());
-    properties.insert("tokenDecimals".into(), 12.into());
-    properties.insert("ss58Format".into(), 42.into());
+/// Load the genesis config patch of the runtime preset `id` from the WASM runtime.
+fn preset(id: &str) -> serde_json::Value {
+    let patch: Option<Vec<u8>> = call_runtime(
+        "GenesisPresetsApi_get_preset",
+        &id.as_bytes().to_vec().encode(),
+    )
+    .expect("the runtime implements `GenesisPresetsApi`");
+    let patch = patch.expect("preset is defined in the runtime");
+    serde_json::from_slice(&patch).expect("presets are valid json; qed")
+}
 
+/// Build a chain spec from the runtime preset `preset_id`.
+fn chain_spec_from_preset(
+    preset_id: &str,
+    name: &str,
+    id: &str,
+    chain_type: ChainType,
+    relay_chain: &str,
+) -> ChainSpec {
     ChainSpec::builder(
-        parachain_template_runtime::WASM_BINARY
-            .expect("WASM binary was not built, please build it!"),
+        wasm_binary(),
         Extensions {
-            relay_chain: "rococo-local".into(),
-            // You MUST set this to the correct network!
-            para_id: 1000,
+            relay_chain: relay_chain.into(),
+            para_id: genesis_config_presets::PARACHAIN_ID,
         },
     )
-    .with_name("Development")
-    .with_id("dev")
-    .with_chain_type(ChainType::Development)
-    .with_genesis_config_patch(testnet_genesis(
-        // initial collators.
-        vec![
-            (
-                get_account_id_from_seed::<sr25519::Public>("Alice"),
-                get_collator_keys_from_seed::<BabeId>("Alice"),
-                get_collator_keys_from_seed::<GrandpaId>("Alice"),
-            ),
-            (
-                get_account_id_from_seed::<sr25519::Public>("Bob"),
-                get_collator_keys_from_seed::<BabeId>("Bob"),
-                get_collator_keys_from_seed::<GrandpaId>("Bob"),
-            ),
-        ],
-        vec![
-            get_account_id_from_seed::<sr25519::Public>("Alice"),
-            get_account_id_from_seed::<sr25519::Public>("Bob"),
-            get_account_id_from_seed::<sr25519::Public>("Charlie"),
-            get_account_id_from_seed::<sr25519::Public>("Dave"),
-            get_account_id_from_seed::<sr25519::Public>("Eve"),
-            get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-            get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-        ],
-        get_account_id_from_seed::<sr25519::Public>("Alice"),
-        1000.into(),
-    ))
+    .with_name(name)
+    .with_id(id)
+    .with_chain_type(chain_type)
+    .with_genesis_config_patch(preset(preset_id))
+    .with_properties(properties())
+}
+
+pub fn development_config() -> ChainSpec {
+    chain_spec_from_preset(
+        genesis_config_presets::DEVELOPMENT,
+        "Development",
+        "dev",
+        ChainType::Development,
+        "rococo-local",
+    )
     .build()
 }
 
 pub fn local_testnet_config() -> ChainSpec {
-    // Give your base currency a unit name and decimal places
-    let mut properties = sc_chain_spec::Properties::new();
-    properties.insert("tokenSymbol".into(), "UNIT".into());
-    properties.insert("tokenDecimals".into(), 12.into());
-    properties.insert("ss58Format".into(), 42.into());
-
-    #[allow(deprecated)]
-    ChainSpec::builder(
-        parachain_template_runtime::WASM_BINARY
-            .expect("WASM binary was not built, please build it!"),
-        Extensions {
-            relay_chain: "rococo-local".into(),
-            // You MUST set this to the correct network!
-            para_id: 1000,
-        },
+    chain_spec_from_preset(
+        genesis_config_presets::LOCAL_TESTNET,
+        "Local Testnet",
+        "local_testnet",
+        ChainType::Local,
+        "rococo-local",
     )
-    .with_name("Local Testnet")
-    .with_id("local_testnet")
-    .with_chain_type(ChainType::Local)
-    .with_genesis_config_patch(testnet_genesis(
-        // initial collators.
-        vec![
-            (
-                get_account_id_from_seed::<sr25519::Public>("Alice"),
-                get_collator_keys_from_seed::<BabeId>("Alice"),
-                get_collator_keys_from_seed::<GrandpaId>("Alice"),
-            ),
-            (
-                get_account_id_from_seed::<sr25519::Public>("Bob"),
-                get_collator_keys_from_seed::<BabeId>("Bob"),
-                get_collator_keys_from_seed::<GrandpaId>("Bob"),
-            ),
-        ],
-        vec![
-            get_account_id_from_seed::<sr25519::Public>("Alice"),
-            get_account_id_from_seed::<sr25519::Public>("Bob"),
-            get_account_id_from_seed::<sr25519::Public>("Charlie"),
-            get_account_id_from_seed::<sr25519::Public>("Dave"),
-            get_account_id_from_seed::<sr25519::Public>("Eve"),
-            get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-            get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-            get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-        ],
-        get_account_id_from_seed::<sr25519::Public>("Alice"),
-        1000.into(),
-    ))
     .with_protocol_id("template-local")
-    .with_properties(properties)
     .build()
 }
 
-fn testnet_genesis(
-    invulnerables: Vec<(AccountId, BabeId, GrandpaId)>,
-    endowed_accounts: Vec<AccountId>,
-    root: AccountId,
-    id: ParaId,
-) -> serde_json::Value {
-    serde_json::json!({
-        "balances": {
-            "balances": endowed_accounts.iter().cloned().map(|k| (k, 1u64 << 60)).collect::<Vec<_>>(),
-        },
-        "parachainInfo": {
-            "parachainId": id,
-        },
-        "collatorSelection": {
-            "invulnerables": invulnerables.iter().cloned().map(|(acc, _babe, _grandpa)| acc).collect::<Vec<_>>(),
-            "candidacyBond": EXISTENTIAL_DEPOSIT * 16,
-        },
-        "session": {
-            "keys": invulnerables
-                .into_iter()
-                .map(|x| {
-                    (
-                        x.0.clone(),                 // account id
-                        x.0.clone(),                 // validator id
-                        template_session_keys(x.1.clone(), x.2.clone()), // session keys
-                    )
-                })
-            .collect::<Vec<_>>(),
-        },
-        "polkadotXcm": {
-            "safeXcmVersion": Some(SAFE_XCM_VERSION),
-        },
-        "sudo": { "key": Some(root) }
-    })
+pub fn staging_config() -> ChainSpec {
+    chain_spec_from_preset(
+        genesis_config_presets::STAGING,
+        "Staging",
+        "staging",
+        ChainType::Live,
+        "rococo",
+    )
+    .with_protocol_id("template-staging")
+    .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_are_read_from_the_runtime() {
+        let names: Vec<Vec<u8>> = call_runtime("GenesisPresetsApi_preset_names", &[]).unwrap();
+        assert_eq!(names.len(), genesis_config_presets::preset_names().len());
+
+        for name in genesis_config_presets::preset_names() {
+            let native = genesis_config_presets::get_preset(name).unwrap();
+            assert_eq!(
+                preset(name),
+                serde_json::from_slice::<serde_json::Value>(&native).unwrap()
+            );
+        }
+    }
 }
@@ -38,10 +38,219 @@ pub enum Subcommand {
     #[command(subcommand)]
     Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 
-    /// Try-runtime has migrated to a standalone
-    /// [CLI](<https://github.com/paritytech/try-runtime-cli>). The subcommand exists as a stub and
-    /// deprecation notice. It will be removed entirely some time after January 2024.
-    TryRuntime,
+    /// Generate a chain specification from a validator manifest.
+    GenerateSpec(GenerateSpecCmd),
+
+    /// Create a chain spec for a test network from the state of a live chain.
+    ForkOff(ForkOffCmd),
+
+    /// Manage the BABE and GRANDPA session keys of this node.
+    #[command(subcommand)]
+    SessionKeys(SessionKeysCmd),
+
+    /// Try-runtime checks against a snapshot of the local database.
+    #[command(subcommand)]
+    TryRuntime(TryRuntimeCmd),
+}
+
+/// Try-runtime sub-commands.
+///
+/// A snapshot holds the state of a block's parent and the block itself. The checks run the
+/// runtime built into this node against it, or the one given with `--runtime`, without any
+/// network access.
+#[derive(Debug, clap::Subcommand)]
+pub enum TryRuntimeCmd {
+    /// Write a snapshot of a block of the local database.
+    CreateSnapshot(CreateSnapshotCmd),
+
+    /// Run the runtime upgrade on the state of a snapshot.
+    OnRuntimeUpgrade(OnRuntimeUpgradeCmd),
+
+    /// Execute the block of a snapshot with try-state checks.
+    ExecuteBlock(ExecuteBlockCmd),
+}
+
+/// The `try-runtime create-snapshot` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct CreateSnapshotCmd {
+    /// File the snapshot is written to.
+    #[arg(value_name = "PATH")]
+    pub path: PathBuf,
+
+    /// Block hash or number of the block in the snapshot. Defaults to the best block.
+    #[arg(long, value_name = "HASH or NUMBER")]
+    pub at: Option<sc_cli::BlockNumberOrHash>,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: sc_cli::SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: sc_cli::DatabaseParams,
+}
+
+/// The `try-runtime on-runtime-upgrade` command.
+#[derive(Debug, Clone, clap::Parser)]
+#[cfg_attr(not(feature = "try-runtime"), allow(dead_code))]
+pub struct OnRuntimeUpgradeCmd {
+    /// The snapshot to run the upgrade on.
+    #[arg(long, value_name = "PATH")]
+    pub snapshot: PathBuf,
+
+    /// Wasm blob of the runtime to test. Defaults to the runtime built into this node.
+    #[arg(long, value_name = "PATH")]
+    pub runtime: Option<PathBuf>,
+
+    /// Which checks to run around the upgrade: `none`, `all`, `pre-and-post` or `try-state`.
+    #[arg(long, default_value = "all")]
+    pub checks: String,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: sc_cli::SharedParams,
+}
+
+/// The `try-runtime execute-block` command.
+#[derive(Debug, Clone, clap::Parser)]
+#[cfg_attr(not(feature = "try-runtime"), allow(dead_code))]
+pub struct ExecuteBlockCmd {
+    /// The snapshot holding the block and the state it is executed on.
+    #[arg(long, value_name = "PATH")]
+    pub snapshot: PathBuf,
+
+    /// Wasm blob of the runtime to test. Defaults to the runtime built into this node.
+    #[arg(long, value_name = "PATH")]
+    pub runtime: Option<PathBuf>,
+
+    /// Which try-state hooks to run after the block: `none`, `all`, `rr-<n>` for round robin over
+    /// `n` pallets or a comma separated list of pallet names, e.g. `Session,Babe`.
+    #[arg(long, default_value = "all")]
+    pub try_state: String,
+
+    /// Check the state root of the block. Only useful if the runtime to test is the one that
+    /// built the block.
+    #[arg(long)]
+    pub state_root_check: bool,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: sc_cli::SharedParams,
+}
+
+/// The `generate-spec` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct GenerateSpecCmd {
+    /// Path to the manifest, TOML if the file ends in `.toml` and JSON otherwise.
+    #[arg(long, value_name = "PATH")]
+    pub manifest: PathBuf,
+
+    /// Output the genesis storage instead of the genesis config.
+    #[arg(long)]
+    pub raw: bool,
+
+    /// File the chain spec is written to. Defaults to stdout.
+    #[arg(long, short, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+/// The `fork-off` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ForkOffCmd {
+    /// Manifest with the validators and para id of the test network.
+    #[arg(long, value_name = "PATH")]
+    pub manifest: PathBuf,
+
+    /// Raw chain spec written by `export-state`. If omitted, the state is read from the database.
+    #[arg(long, value_name = "PATH", conflicts_with = "at")]
+    pub state: Option<PathBuf>,
+
+    /// Block hash or number whose state is forked off. Defaults to the best block.
+    #[arg(long, value_name = "HASH or NUMBER")]
+    pub at: Option<sc_cli::BlockNumberOrHash>,
+
+    /// File the raw chain spec is written to. Defaults to stdout.
+    #[arg(long, short, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: sc_cli::SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: sc_cli::DatabaseParams,
+}
+
+/// Session key management sub-commands.
+///
+/// Our session keys consist of a BABE sr25519 key and a GRANDPA ed25519 key. The commands print
+/// them SCALE-encoded, ready to be passed to `session.setKeys`.
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionKeysCmd {
+    /// Generate new session keys and store them in the keystore.
+    Generate(GenerateSessionKeysCmd),
+
+    /// Derive the session keys from a secret URI and store them in the keystore.
+    Insert(InsertSessionKeysCmd),
+
+    /// Verify that the keystore holds the private keys of the session keys registered on chain.
+    Verify(VerifySessionKeysCmd),
+}
+
+/// The `session-keys generate` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct GenerateSessionKeysCmd {
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: sc_cli::SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub keystore_params: sc_cli::KeystoreParams,
+}
+
+/// The `session-keys insert` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct InsertSessionKeysCmd {
+    /// The secret URI both keys are derived from.
+    ///
+    /// If the value is a file, the file content is used as URI. If omitted, you will be
+    /// prompted for the URI.
+    #[arg(long)]
+    pub suri: Option<String>,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: sc_cli::SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub keystore_params: sc_cli::KeystoreParams,
+}
+
+/// The `session-keys verify` command.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct VerifySessionKeysCmd {
+    /// The SS58 address of the validator whose registered keys are checked.
+    #[arg(long, value_name = "SS58_ADDRESS")]
+    pub validator: String,
+
+    /// Block hash or number at which the registered keys are read. Defaults to the best block.
+    #[arg(long, value_name = "HASH or NUMBER")]
+    pub at: Option<sc_cli::BlockNumberOrHash>,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub shared_params: sc_cli::SharedParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub keystore_params: sc_cli::KeystoreParams,
+
+    #[allow(missing_docs)]
+    #[clap(flatten)]
+    pub database_params: sc_cli::DatabaseParams,
 }
 
 const AFTER_HELP_EXAMPLE: &str = color_print::cstr!(
@@ -0,0 +1,181 @@
+//! Submission of the blocks this node authors to the relay chain, as collations.
+//!
+//! BABE authors blocks on its own slots, outside of the collators cumulus ships. For every block
+//! it authors, the storage proof of validating the block is recorded, packed into a collation with
+//! the block and handed to the collation generation subsystem of the relay chain node, which
+//! distributes it to the validators of the parachain.
+
+use std::sync::Arc;
+
+use codec::Encode;
+use cumulus_client_collator::service::CollatorService;
+use cumulus_client_consensus_common::ParachainCandidate;
+use cumulus_primitives_core::{
+    relay_chain::{CollatorPair, ValidationCode},
+    rpsr_digest::extract_relay_parent_storage_root,
+    CollectCollationInfo, ParaId,
+};
+use cumulus_relay_chain_interface::{BlockId, OverseerHandle, RelayChainInterface};
+use futures::StreamExt;
+use parachain_template_runtime::opaque::{Block, Hash};
+use polkadot_node_primitives::SubmitCollationParams;
+use polkadot_node_subsystem::messages::CollationGenerationMessage;
+use sc_client_api::{Backend, BlockBackend, BlockchainEvents, StorageKey, StorageProvider};
+use sp_api::{ApiExt, Core, ProvideRuntimeApi, StorageProof};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_consensus_babe::{digests::CompatibleDigestItem, BabeApi};
+use sp_core::storage::well_known_keys;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+
+/// Log target of this module.
+const LOG_TARGET: &str = "collation";
+
+/// Register as collator of `para_id` with the relay chain node and submit a collation for every
+/// block the node authors from then on.
+pub async fn run<C, BE>(
+    client: Arc<C>,
+    collator_service: CollatorService<Block, C, C>,
+    relay_chain_interface: Arc<dyn RelayChainInterface>,
+    mut overseer_handle: OverseerHandle,
+    collator_key: CollatorPair,
+    para_id: ParaId,
+) where
+    C: BlockchainEvents<Block>
+        + BlockBackend<Block>
+        + HeaderBackend<Block>
+        + StorageProvider<Block, BE>
+        + ProvideRuntimeApi<Block>
+        + Send
+        + Sync
+        + 'static,
+    C::Api: Core<Block> + BabeApi<Block> + CollectCollationInfo<Block>,
+    BE: Backend<Block>,
+{
+    cumulus_client_collator::initialize_collator_subsystems(
+        &mut overseer_handle,
+        collator_key,
+        para_id,
+        false,
+    )
+    .await;
+
+    let mut import_notifications = client.import_notification_stream();
+
+    while let Some(notification) = import_notifications.next().await {
+        if notification.origin != BlockOrigin::Own {
+            continue;
+        }
+
+        match collation(
+            &*client,
+            &collator_service,
+            &*relay_chain_interface,
+            notification.hash,
+        )
+        .await
+        {
+            Ok(params) => {
+                overseer_handle
+                    .send_msg(
+                        CollationGenerationMessage::SubmitCollation(params),
+                        "SubmitCollation",
+                    )
+                    .await
+            }
+            Err(err) => log::warn!(
+                target: LOG_TARGET,
+                "Could not build the collation of block {}: {}",
+                notification.hash,
+                err,
+            ),
+        }
+    }
+}
+
+/// The collation of the block `hash`, with the relay chain block the block was built on as its
+/// relay parent.
+async fn collation<C, BE>(
+    client: &C,
+    collator_service: &CollatorService<Block, C, C>,
+    relay_chain_interface: &dyn RelayChainInterface,
+    hash: Hash,
+) -> Result<SubmitCollationParams, String>
+where
+    C: BlockBackend<Block>
+        + HeaderBackend<Block>
+        + StorageProvider<Block, BE>
+        + ProvideRuntimeApi<Block>,
+    C::Api: Core<Block> + BabeApi<Block> + CollectCollationInfo<Block>,
+    BE: Backend<Block>,
+{
+    let block = client
+        .block(hash)
+        .map_err(|e| e.to_string())?
+        .ok_or("The block is not in the database")?
+        .block;
+    let parent_hash = *block.header().parent_hash();
+    let parent_header = client
+        .header(parent_hash)
+        .map_err(|e| e.to_string())?
+        .ok_or("The parent block is not in the database")?;
+
+    // The parachain inherent leaves the storage root and number of the relay parent in a digest.
+    let (storage_root, number) = extract_relay_parent_storage_root(block.header().digest())
+        .ok_or("The block names no relay parent")?;
+    let relay_parent = relay_chain_interface
+        .header(BlockId::Number(number))
+        .await
+        .map_err(|e| e.to_string())?
+        .filter(|header| header.state_root == storage_root)
+        .ok_or("The relay parent is unknown")?
+        .hash();
+
+    let code = client
+        .storage(parent_hash, &StorageKey(well_known_keys::CODE.to_vec()))
+        .map_err(|e| e.to_string())?
+        .ok_or("The parent block has no runtime code")?;
+    let validation_code_hash = ValidationCode(code.0).hash();
+
+    let proof = validation_proof(client, parent_hash, block.clone())?;
+    let (collation, _) = collator_service
+        .build_collation(&parent_header, hash, ParachainCandidate { block, proof })
+        .ok_or("The collation could not be built")?;
+
+    Ok(SubmitCollationParams {
+        relay_parent,
+        collation,
+        parent_head: parent_header.encode().into(),
+        validation_code_hash,
+        result_sender: None,
+    })
+}
+
+/// The storage proof of validating `block` on top of `parent_hash`.
+///
+/// Validation checks the seal against the BABE authorities before it executes the block without
+/// the seal, see `PalletBabeBlockExecutor` of the runtime, so the epochs are read first.
+fn validation_proof<C>(client: &C, parent_hash: Hash, block: Block) -> Result<StorageProof, String>
+where
+    C: ProvideRuntimeApi<Block>,
+    C::Api: Core<Block> + BabeApi<Block>,
+{
+    let (mut header, extrinsics) = block.deconstruct();
+    match header.digest_mut().pop() {
+        Some(item) if item.as_babe_seal().is_some() => {}
+        _ => return Err("The block has no BABE seal".into()),
+    }
+
+    let mut runtime_api = client.runtime_api();
+    runtime_api.record_proof();
+    runtime_api
+        .current_epoch_start(parent_hash)
+        .and_then(|_| runtime_api.current_epoch(parent_hash))
+        .and_then(|_| runtime_api.next_epoch(parent_hash))
+        .and_then(|_| runtime_api.execute_block(parent_hash, Block::new(header, extrinsics)))
+        .map_err(|e| e.to_string())?;
+
+    runtime_api
+        .extract_proof()
+        .ok_or_else(|| "No storage proof was recorded".into())
+}
@@ -0,0 +1,364 @@
+//! Implementation of the `fork-off` sub-command.
+//!
+//! Takes the state of a live chain and rewrites the parts that tie it to its network: the
+//! validators and their session keys, the para id and the relay chain slot duration, all taken
+//! from a manifest as used by `generate-spec`. The endowments of the manifest are added on top, so
+//! its accounts can pay for their calls and referenda. The result is a raw chain spec whose
+//! genesis is the live state, so runtime upgrades and migrations can be rehearsed on a local
+//! network.
+
+use std::{path::Path, sync::Arc};
+
+use codec::{Decode, Encode};
+use parachain_template_runtime::{opaque::Block, Balance, Nonce, SessionKeys};
+use sc_chain_spec::ChainSpec as _;
+use sc_client_api::{StorageProvider, UsageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+    hashing::{blake2_128, twox_128, twox_64},
+    storage::{well_known_keys, Storage},
+};
+use sp_runtime::{traits::OpaqueKeys, BuildStorage};
+
+use crate::{chain_spec, cli::ForkOffCmd, manifest::Manifest};
+
+type AccountInfo = frame_system::AccountInfo<Nonce, pallet_balances::AccountData<Balance>>;
+
+/// Key of a storage value.
+fn value_key(pallet: &str, item: &str) -> Vec<u8> {
+    [twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// Key of a `Twox64Concat` storage map entry.
+fn twox_64_concat_key(pallet: &str, item: &str, key: &impl Encode) -> Vec<u8> {
+    let key = key.encode();
+    [value_key(pallet, item), twox_64(&key).to_vec(), key].concat()
+}
+
+/// Key of a `Blake2_128Concat` storage map entry.
+fn blake2_128_concat_key(pallet: &str, item: &str, key: &impl Encode) -> Vec<u8> {
+    let key = key.encode();
+    [value_key(pallet, item), blake2_128(&key).to_vec(), key].concat()
+}
+
+/// Remove all keys starting with `prefix`.
+fn clear_prefix(storage: &mut Storage, prefix: &[u8]) {
+    storage.top.retain(|key, _| !key.starts_with(prefix));
+}
+
+/// Rewrite `storage` to be run by the network described in `manifest`.
+pub fn fork_off(storage: &mut Storage, manifest: &Manifest) -> Result<(), String> {
+    let validators = manifest.validators().map_err(|e| e.to_string())?;
+    let stashes = validators
+        .iter()
+        .map(|(stash, _)| stash.clone())
+        .collect::<Vec<_>>();
+    let babe_authorities = validators
+        .iter()
+        .map(|(_, keys)| (keys.babe.clone(), 1u64))
+        .collect::<Vec<_>>();
+    let grandpa_authorities = validators
+        .iter()
+        .map(|(_, keys)| (keys.grandpa.clone(), 1u64))
+        .collect::<Vec<_>>();
+
+    // Messaging and validation state of parachain system refer to the relay chain of the live
+    // network and are rebuilt from the first inherent on the new one. Candidates, old session
+    // keys and the authority set history belong to the validators we replace.
+    for prefix in [
+        twox_128(b"ParachainSystem").to_vec(),
+        value_key("CollatorSelection", "LastAuthoredBlock"),
+        value_key("Session", "NextKeys"),
+        value_key("Session", "KeyOwner"),
+        value_key("Babe", "UnderConstruction"),
+        value_key("Grandpa", "SetIdSession"),
+    ] {
+        clear_prefix(storage, &prefix);
+    }
+
+    // Resetting the BABE genesis slot makes the runtime start over at epoch 0 in the first block
+    // and announce the new authorities, just as it does on a fresh chain. Likewise the node starts
+    // the new chain with GRANDPA authority set 0.
+    let top = &mut storage.top;
+    for key in [
+        value_key("CollatorSelection", "CandidateList"),
+        value_key("Session", "DisabledValidators"),
+        value_key("Babe", "GenesisSlot"),
+        value_key("Babe", "CurrentSlot"),
+        value_key("Babe", "EpochIndex"),
+        value_key("Babe", "EpochStart"),
+        value_key("Babe", "SkippedEpochs"),
+        value_key("Babe", "SegmentIndex"),
+        value_key("Babe", "Lateness"),
+        value_key("Babe", "PendingEpochConfigChange"),
+        value_key("Grandpa", "CurrentSetId"),
+        value_key("Grandpa", "PendingChange"),
+        value_key("Grandpa", "NextForced"),
+        value_key("Grandpa", "Stalled"),
+        value_key("Grandpa", "State"),
+        sp_consensus_grandpa::GRANDPA_AUTHORITIES_KEY.to_vec(),
+    ] {
+        top.remove(&key);
+    }
+
+    top.insert(
+        value_key("ParachainInfo", "ParachainId"),
+        manifest.para_id.encode(),
+    );
+    top.insert(
+        value_key("RelayChainSlot", "SlotDurationMillis"),
+        manifest.relay_chain_slot_duration_millis().encode(),
+    );
+
+    // The validators are the invulnerable collators and active right away, with their keys
+    // queued for the next session as well.
+    top.insert(
+        value_key("CollatorSelection", "Invulnerables"),
+        stashes.encode(),
+    );
+    for (stash, keys) in &validators {
+        top.insert(
+            twox_64_concat_key("Session", "NextKeys", stash),
+            keys.encode(),
+        );
+        for key_type in SessionKeys::key_ids() {
+            let owner_key = (*key_type, keys.get_raw(*key_type).to_vec());
+            top.insert(
+                twox_64_concat_key("Session", "KeyOwner", &owner_key),
+                stash.encode(),
+            );
+        }
+    }
+    top.insert(value_key("Session", "Validators"), stashes.encode());
+    top.insert(value_key("Session", "QueuedKeys"), validators.encode());
+    top.insert(value_key("Session", "QueuedChanged"), false.encode());
+
+    top.insert(value_key("Babe", "Authorities"), babe_authorities.encode());
+    top.insert(
+        value_key("Babe", "NextAuthorities"),
+        babe_authorities.encode(),
+    );
+    top.insert(
+        value_key("Grandpa", "Authorities"),
+        grandpa_authorities.encode(),
+    );
+
+    endow(storage, manifest)
+}
+
+/// Add the endowments of `manifest` to the free balance of the accounts.
+fn endow(storage: &mut Storage, manifest: &Manifest) -> Result<(), String> {
+    let total_issuance_key = value_key("Balances", "TotalIssuance");
+    let mut total_issuance = storage
+        .top
+        .get(&total_issuance_key)
+        .map(|data| Balance::decode(&mut &data[..]))
+        .transpose()
+        .map_err(|e| format!("Failed to decode the total issuance: {}", e))?
+        .unwrap_or_default();
+
+    for (account, amount) in manifest.endowments().map_err(|e| e.to_string())? {
+        let key = blake2_128_concat_key("System", "Account", &account);
+        let mut info = match storage.top.get(&key) {
+            Some(data) => AccountInfo::decode(&mut &data[..])
+                .map_err(|e| format!("Failed to decode the account of {}: {}", account, e))?,
+            None => AccountInfo {
+                providers: 1,
+                ..Default::default()
+            },
+        };
+        info.data.free = info.data.free.saturating_add(amount);
+        total_issuance = total_issuance.saturating_add(amount);
+        storage.top.insert(key, info.encode());
+    }
+
+    storage
+        .top
+        .insert(total_issuance_key, total_issuance.encode());
+
+    Ok(())
+}
+
+impl ForkOffCmd {
+    /// Run the command on state exported with `export-state`.
+    pub fn run_on_exported_state(&self, path: &Path) -> sc_cli::Result<()> {
+        let storage = chain_spec::ChainSpec::from_json_file(path.to_path_buf())?.build_storage()?;
+
+        self.write_fork(storage)
+    }
+
+    /// Run the command on the state of the local database.
+    pub fn run_on_database<C, BE>(&self, client: Arc<C>) -> sc_cli::Result<()>
+    where
+        C: HeaderBackend<Block> + StorageProvider<Block, BE> + UsageProvider<Block>,
+        BE: sc_client_api::Backend<Block>,
+    {
+        let at = match &self.at {
+            Some(at) => {
+                let id = at.parse::<Block>()?;
+                client
+                    .block_hash_from_id(&id)?
+                    .ok_or_else(|| format!("Block {} not found", at))?
+            }
+            None => client.info().best_hash,
+        };
+        log::info!("Forking off the state at {:?}", at);
+
+        let storage = sc_service::chain_ops::export_raw_state(client, at)?;
+
+        self.write_fork(storage)
+    }
+
+    fn write_fork(&self, mut storage: Storage) -> sc_cli::Result<()> {
+        let manifest = Manifest::from_file(&self.manifest)?;
+        fork_off(&mut storage, &manifest)?;
+
+        let code = storage
+            .top
+            .get(well_known_keys::CODE)
+            .cloned()
+            .ok_or("The state has no runtime code")?;
+        let mut chain_spec = manifest.chain_spec(&code, serde_json::json!({}));
+        chain_spec.set_storage(storage);
+        let json = chain_spec.as_json(true)?;
+
+        match &self.output {
+            Some(path) => std::fs::write(path, json)?,
+            None => println!("{}", json),
+        }
+
+        Ok(())
+    }
+}
+
+impl sc_cli::CliConfiguration for ForkOffCmd {
+    fn shared_params(&self) -> &sc_cli::SharedParams {
+        &self.shared_params
+    }
+
+    fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::tests::manifest;
+    use parachain_template_runtime::{AccountId, BabeId, GrandpaId};
+
+    fn decode<T: Decode>(storage: &Storage, key: &[u8]) -> T {
+        T::decode(&mut &storage.top[key][..]).unwrap()
+    }
+
+    fn stash() -> AccountId {
+        manifest().validators().unwrap()[0].0.clone()
+    }
+
+    #[test]
+    fn removes_state_of_the_live_network() {
+        let old_validator = AccountId::from([1; 32]);
+        let identity = blake2_128_concat_key("Identity", "IdentityOf", &old_validator);
+        let mut storage = Storage::default();
+        for key in [
+            value_key("ParachainSystem", "LastDmqMqcHead"),
+            value_key("CollatorSelection", "CandidateList"),
+            twox_64_concat_key("Session", "NextKeys", &old_validator),
+            value_key("Babe", "GenesisSlot"),
+            value_key("Grandpa", "CurrentSetId"),
+            sp_consensus_grandpa::GRANDPA_AUTHORITIES_KEY.to_vec(),
+            identity.clone(),
+        ] {
+            storage.top.insert(key, vec![1]);
+        }
+
+        fork_off(&mut storage, &manifest()).unwrap();
+
+        for key in [
+            value_key("ParachainSystem", "LastDmqMqcHead"),
+            value_key("CollatorSelection", "CandidateList"),
+            twox_64_concat_key("Session", "NextKeys", &old_validator),
+            value_key("Babe", "GenesisSlot"),
+            value_key("Grandpa", "CurrentSetId"),
+            sp_consensus_grandpa::GRANDPA_AUTHORITIES_KEY.to_vec(),
+        ] {
+            assert!(!storage.top.contains_key(&key));
+        }
+        assert_eq!(storage.top[&identity], vec![1]);
+    }
+
+    #[test]
+    fn rewrites_authorities_and_collators() {
+        let manifest = manifest();
+        let (stash, keys) = manifest.validators().unwrap().remove(0);
+        let mut storage = Storage::default();
+
+        fork_off(&mut storage, &manifest).unwrap();
+
+        assert_eq!(
+            decode::<u32>(&storage, &value_key("ParachainInfo", "ParachainId")),
+            2000
+        );
+        assert_eq!(
+            decode::<u32>(&storage, &value_key("RelayChainSlot", "SlotDurationMillis")),
+            6000
+        );
+        assert_eq!(
+            decode::<Vec<AccountId>>(&storage, &value_key("CollatorSelection", "Invulnerables")),
+            vec![stash.clone()]
+        );
+        assert_eq!(
+            decode::<Vec<AccountId>>(&storage, &value_key("Session", "Validators")),
+            vec![stash.clone()]
+        );
+        assert_eq!(
+            decode::<SessionKeys>(&storage, &twox_64_concat_key("Session", "NextKeys", &stash)),
+            keys.clone()
+        );
+        let owner_key = (
+            sp_consensus_babe::KEY_TYPE,
+            keys.get_raw(sp_consensus_babe::KEY_TYPE).to_vec(),
+        );
+        assert_eq!(
+            decode::<AccountId>(
+                &storage,
+                &twox_64_concat_key("Session", "KeyOwner", &owner_key)
+            ),
+            stash
+        );
+        assert_eq!(
+            decode::<Vec<(BabeId, u64)>>(&storage, &value_key("Babe", "Authorities")),
+            vec![(keys.babe.clone(), 1)]
+        );
+        assert_eq!(
+            decode::<Vec<(GrandpaId, u64)>>(&storage, &value_key("Grandpa", "Authorities")),
+            vec![(keys.grandpa, 1)]
+        );
+    }
+
+    #[test]
+    fn endowments_are_added_to_existing_balances() {
+        let account_key = blake2_128_concat_key("System", "Account", &stash());
+        let mut account = AccountInfo {
+            providers: 1,
+            ..Default::default()
+        };
+        account.data.free = 10;
+        let mut storage = Storage::default();
+        storage.top.insert(account_key.clone(), account.encode());
+        storage
+            .top
+            .insert(value_key("Balances", "TotalIssuance"), 10u128.encode());
+
+        fork_off(&mut storage, &manifest()).unwrap();
+
+        // The manifest endows the stash with its bond of 1000 and a balance of 500.
+        let account = decode::<AccountInfo>(&storage, &account_key);
+        assert_eq!(account.data.free, 1510);
+        assert_eq!(account.providers, 1);
+        assert_eq!(
+            decode::<Balance>(&storage, &value_key("Balances", "TotalIssuance")),
+            1510
+        );
+    }
+}
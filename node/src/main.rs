@@ -2,11 +2,20 @@
 
 #![warn(missing_docs)]
 
+mod block_announce;
 mod chain_spec;
 mod cli;
+mod collation;
 mod command;
+mod fork_off;
+mod manifest;
+mod metrics;
+#[cfg(test)]
+mod relay_chain_mock;
 mod rpc;
 mod service;
+mod session_keys;
+mod try_runtime;
 
 fn main() -> sc_cli::Result<()> {
     command::run()
@@ -0,0 +1,559 @@
+//! Chain specs generated from a validator manifest.
+//!
+//! The presets of the runtime use the well-known development keys, which is fine for local
+//! networks but not for a testnet run by independent operators. A manifest lists the real keys
+//! of the validators together with the rest of the network configuration, for example:
+//!
+//! ```toml
+//! name = "Staging Testnet"
+//! id = "staging_testnet"
+//! chain_type = "Live"
+//! para_id = 2000
+//! relay_chain = "rococo"
+//! # Defaults to the six seconds of production relay chains.
+//! relay_chain_slot_duration_millis = 6000
+//! candidacy_bond = 16000000000
+//!
+//! [properties]
+//! token_symbol = "UNIT"
+//! token_decimals = 12
+//! ss58_format = 42
+//!
+//! [[validators]]
+//! stash = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! babe = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+//! grandpa = "0x88dc3417d5058ec4b4503e0c12ea1a0a89be200fe98922423d4334014fa6b0ee"
+//! bond = 1000000000000000
+//!
+//! [[endowed]]
+//! account = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+//! balance = 1000000000000000
+//!
+//! [[endowed]]
+//! account = "5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y"
+//! balance = 1000000000000000
+//! # A tenth is liquid, the rest vests linearly over the first year.
+//! vesting = { begin = 0, length = 10512000, liquid = 100000000000000 }
+//! ```
+//!
+//! Validators join as invulnerable collators. The runtime has no staking pallet yet, so their
+//! bond is added to the endowment of the stash, to be bonded once staking is enabled.
+
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use parachain_template_runtime::{
+    genesis_config_presets::GenesisVesting, relay_chain_slot, AccountId, BabeId, Balance,
+    BlockNumber, GrandpaId, SessionKeys,
+};
+use sc_service::ChainType;
+use serde::Deserialize;
+use sp_core::{
+    crypto::{ByteArray, Ss58Codec},
+    ed25519, sr25519,
+};
+use sp_runtime::BuildStorage;
+
+use crate::{
+    chain_spec::{ChainSpec, Extensions},
+    cli::GenerateSpecCmd,
+};
+
+/// The network configuration an operator hands to `generate-spec`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Human readable name of the network.
+    pub name: String,
+    /// Identifier of the network, also used for the directory the chain data is stored in.
+    pub id: String,
+    /// One of `Development`, `Local` or `Live`.
+    pub chain_type: ChainType,
+    /// The id of the parachain.
+    pub para_id: u32,
+    /// The name of the relay chain spec the parachain connects to.
+    pub relay_chain: String,
+    /// The slot duration of the relay chain in milliseconds, defaults to the six seconds of
+    /// production relay chains. The node refuses to start if it doesn't match the relay chain.
+    #[serde(default)]
+    pub relay_chain_slot_duration_millis: Option<u32>,
+    /// The network protocol id, defaults to the protocol id derived from the chain id.
+    #[serde(default)]
+    pub protocol_id: Option<String>,
+    /// Token properties shown by user interfaces.
+    pub properties: TokenProperties,
+    /// The bond collator candidates have to reserve.
+    pub candidacy_bond: Balance,
+    /// The initial validators.
+    pub validators: Vec<Validator>,
+    /// Accounts endowed in genesis, in addition to the validator bonds.
+    #[serde(default)]
+    pub endowed: Vec<Endowed>,
+}
+
+/// Token properties of a [`Manifest`].
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenProperties {
+    /// The symbol of the native token.
+    pub token_symbol: String,
+    /// The number of decimals of the native token.
+    pub token_decimals: u8,
+    /// The SS58 prefix of addresses on the network.
+    pub ss58_format: u16,
+}
+
+/// A genesis validator of a [`Manifest`].
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Validator {
+    /// SS58 address of the stash account.
+    pub stash: String,
+    /// BABE public key, as SS58 address or hex.
+    pub babe: String,
+    /// GRANDPA public key, as SS58 address or hex.
+    pub grandpa: String,
+    /// The amount bonded by the stash.
+    pub bond: Balance,
+}
+
+/// An endowed account of a [`Manifest`].
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Endowed {
+    /// SS58 address of the account.
+    pub account: String,
+    /// The free balance of the account.
+    pub balance: Balance,
+    /// Locks the balance over time, if set.
+    #[serde(default)]
+    pub vesting: Option<Vesting>,
+}
+
+/// The vesting schedule of an [`Endowed`] account.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Vesting {
+    /// The block the balance starts to vest at.
+    pub begin: BlockNumber,
+    /// The number of blocks it takes until the balance has fully vested.
+    pub length: BlockNumber,
+    /// The part of the balance that is liquid from the start.
+    pub liquid: Balance,
+}
+
+/// Errors while turning a [`Manifest`] into a chain spec.
+#[derive(Debug)]
+pub enum ManifestError {
+    /// The manifest could not be read.
+    Io(std::io::Error),
+    /// The manifest is not valid TOML or JSON, or has unexpected fields.
+    Parse(String),
+    /// A key or address in the manifest is malformed.
+    InvalidKey {
+        field: String,
+        value: String,
+        reason: String,
+    },
+    /// The manifest is well-formed, but describes an unusable network.
+    Invalid(String),
+    /// The runtime rejected the resulting genesis config.
+    Genesis(String),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ManifestError::Io(e) => write!(f, "Failed to read the manifest: {}", e),
+            ManifestError::Parse(e) => write!(f, "Failed to parse the manifest: {}", e),
+            ManifestError::InvalidKey {
+                field,
+                value,
+                reason,
+            } => {
+                write!(f, "Invalid `{}` value `{}`: {}", field, value, reason)
+            }
+            ManifestError::Invalid(e) => write!(f, "Invalid manifest: {}", e),
+            ManifestError::Genesis(e) => {
+                write!(f, "The runtime rejected the genesis config: {}", e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+impl From<ManifestError> for sc_cli::Error {
+    fn from(e: ManifestError) -> Self {
+        sc_cli::Error::Input(e.to_string())
+    }
+}
+
+/// Parse a public key given either as SS58 address or as `0x` prefixed hex.
+fn parse_public<P: ByteArray + Ss58Codec>(field: &str, value: &str) -> Result<P, ManifestError> {
+    let invalid = |reason: String| ManifestError::InvalidKey {
+        field: field.into(),
+        value: value.into(),
+        reason,
+    };
+
+    if value.starts_with("0x") {
+        let bytes =
+            sp_core::bytes::from_hex(value).map_err(|e| invalid(format!("invalid hex: {}", e)))?;
+        P::from_slice(&bytes)
+            .map_err(|_| invalid(format!("expected {} bytes, got {}", P::LEN, bytes.len())))
+    } else {
+        P::from_ss58check(value).map_err(|e| invalid(format!("invalid SS58 address: {}", e)))
+    }
+}
+
+fn parse_account(field: &str, value: &str) -> Result<AccountId, ManifestError> {
+    parse_public::<sr25519::Public>(field, value).map(Into::into)
+}
+
+/// Add `amount` to the endowment of `account`.
+fn add_endowment(
+    balances: &mut BTreeMap<AccountId, Balance>,
+    account: AccountId,
+    amount: Balance,
+) -> Result<(), ManifestError> {
+    let overflow = || {
+        ManifestError::Invalid(format!(
+            "the endowment of {} overflows",
+            account.to_ss58check()
+        ))
+    };
+    let balance = balances.get(&account).copied().unwrap_or_default();
+    let balance = balance.checked_add(amount).ok_or_else(overflow)?;
+    balances.insert(account, balance);
+    Ok(())
+}
+
+impl Manifest {
+    /// Read a manifest from `path`. Files ending in `.toml` are parsed as TOML, all others as JSON.
+    pub fn from_file(path: &Path) -> Result<Self, ManifestError> {
+        let content = std::fs::read_to_string(path).map_err(ManifestError::Io)?;
+        if path.extension().map_or(false, |ext| ext == "toml") {
+            toml::from_str(&content).map_err(|e| ManifestError::Parse(e.to_string()))
+        } else {
+            serde_json::from_str(&content).map_err(|e| ManifestError::Parse(e.to_string()))
+        }
+    }
+
+    /// The slot duration of the relay chain, in milliseconds.
+    pub fn relay_chain_slot_duration_millis(&self) -> u32 {
+        self.relay_chain_slot_duration_millis
+            .unwrap_or(relay_chain_slot::DEFAULT_SLOT_DURATION_MILLIS)
+    }
+
+    /// The stash accounts of the validators together with their session keys.
+    pub fn validators(&self) -> Result<Vec<(AccountId, SessionKeys)>, ManifestError> {
+        if self.validators.is_empty() {
+            return Err(ManifestError::Invalid(
+                "at least one validator is required".into(),
+            ));
+        }
+
+        let mut validators = Vec::<(AccountId, SessionKeys)>::with_capacity(self.validators.len());
+        for (i, validator) in self.validators.iter().enumerate() {
+            let stash = parse_account(&format!("validators[{}].stash", i), &validator.stash)?;
+            let babe: BabeId = parse_public::<sr25519::Public>(
+                &format!("validators[{}].babe", i),
+                &validator.babe,
+            )?
+            .into();
+            let grandpa: GrandpaId = parse_public::<ed25519::Public>(
+                &format!("validators[{}].grandpa", i),
+                &validator.grandpa,
+            )?
+            .into();
+
+            if validators.iter().any(|(account, _)| *account == stash) {
+                return Err(ManifestError::Invalid(format!(
+                    "validator {} is listed more than once",
+                    validator.stash
+                )));
+            }
+
+            validators.push((stash, SessionKeys { babe, grandpa }));
+        }
+
+        Ok(validators)
+    }
+
+    /// The amount every account is endowed with, including the validator bonds.
+    pub fn endowments(&self) -> Result<BTreeMap<AccountId, Balance>, ManifestError> {
+        let mut balances = BTreeMap::<AccountId, Balance>::new();
+
+        for (i, validator) in self.validators.iter().enumerate() {
+            let stash = parse_account(&format!("validators[{}].stash", i), &validator.stash)?;
+            add_endowment(&mut balances, stash, validator.bond)?;
+        }
+
+        for (i, endowed) in self.endowed.iter().enumerate() {
+            let account = parse_account(&format!("endowed[{}].account", i), &endowed.account)?;
+            add_endowment(&mut balances, account, endowed.balance)?;
+        }
+
+        Ok(balances)
+    }
+
+    /// The vesting schedules of the endowed accounts.
+    pub fn vesting(&self) -> Result<Vec<GenesisVesting>, ManifestError> {
+        let mut vesting = Vec::new();
+
+        for (i, endowed) in self.endowed.iter().enumerate() {
+            let Some(schedule) = &endowed.vesting else {
+                continue;
+            };
+            if schedule.length == 0 || schedule.liquid > endowed.balance {
+                return Err(ManifestError::Invalid(format!(
+                    "endowed[{}].vesting must last at least one block and leave at most the \
+                     balance liquid",
+                    i
+                )));
+            }
+            let account = parse_account(&format!("endowed[{}].account", i), &endowed.account)?;
+            vesting.push((account, schedule.begin, schedule.length, schedule.liquid));
+        }
+
+        Ok(vesting)
+    }
+
+    /// The genesis config patch described by the manifest.
+    pub fn genesis_config_patch(&self) -> Result<serde_json::Value, ManifestError> {
+        let validators = self.validators()?;
+
+        let patch = serde_json::json!({
+            "balances": {
+                "balances": self.endowments()?.into_iter().collect::<Vec<_>>(),
+            },
+            "vesting": {
+                "vesting": self.vesting()?,
+            },
+            "parachainInfo": {
+                "parachainId": self.para_id,
+            },
+            "relayChainSlot": {
+                "slotDurationMillis": self.relay_chain_slot_duration_millis(),
+            },
+            "collatorSelection": {
+                "invulnerables": validators.iter().map(|(stash, _)| stash).collect::<Vec<_>>(),
+                "candidacyBond": self.candidacy_bond,
+            },
+            "session": {
+                "keys": validators
+                    .iter()
+                    .map(|(stash, keys)| (stash, stash, keys))
+                    .collect::<Vec<_>>(),
+            },
+            "polkadotXcm": {
+                "safeXcmVersion": Some(xcm::prelude::XCM_VERSION),
+            },
+        });
+
+        Ok(patch)
+    }
+
+    /// A chain spec with the metadata of the manifest, running `code` with the given genesis
+    /// config patch.
+    pub fn chain_spec(&self, code: &[u8], patch: serde_json::Value) -> ChainSpec {
+        let mut properties = sc_chain_spec::Properties::new();
+        properties.insert(
+            "tokenSymbol".into(),
+            self.properties.token_symbol.clone().into(),
+        );
+        properties.insert(
+            "tokenDecimals".into(),
+            self.properties.token_decimals.into(),
+        );
+        properties.insert("ss58Format".into(), self.properties.ss58_format.into());
+
+        let mut builder = ChainSpec::builder(
+            code,
+            Extensions {
+                relay_chain: self.relay_chain.clone(),
+                para_id: self.para_id,
+            },
+        )
+        .with_name(&self.name)
+        .with_id(&self.id)
+        .with_chain_type(self.chain_type.clone())
+        .with_genesis_config_patch(patch)
+        .with_properties(properties);
+
+        if let Some(protocol_id) = &self.protocol_id {
+            builder = builder.with_protocol_id(protocol_id);
+        }
+
+        builder.build()
+    }
+
+    /// Build the chain spec described by the manifest and check that the runtime accepts it.
+    pub fn into_chain_spec(self) -> Result<ChainSpec, ManifestError> {
+        let chain_spec = self.chain_spec(
+            parachain_template_runtime::WASM_BINARY
+                .expect("WASM binary was not built, please build it!"),
+            self.genesis_config_patch()?,
+        );
+
+        // Building the storage runs the patch through the runtime's `GenesisBuilder`, which
+        // rejects unknown fields and values the pallets can't decode.
+        chain_spec.build_storage().map_err(ManifestError::Genesis)?;
+
+        Ok(chain_spec)
+    }
+}
+
+impl GenerateSpecCmd {
+    /// Run the command.
+    pub fn run(&self) -> sc_cli::Result<()> {
+        let chain_spec = Manifest::from_file(&self.manifest)?.into_chain_spec()?;
+        let json = sc_service::ChainSpec::as_json(&chain_spec, self.raw)?;
+
+        match &self.output {
+            Some(path) => std::fs::write(path, json)?,
+            None => println!("{}", json),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const MANIFEST: &str = r#"
+        name = "Staging Testnet"
+        id = "staging_testnet"
+        chain_type = "Live"
+        para_id = 2000
+        relay_chain = "rococo"
+        candidacy_bond = 100
+
+        [properties]
+        token_symbol = "UNIT"
+        token_decimals = 12
+        ss58_format = 42
+
+        [[validators]]
+        stash = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+        babe = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+        grandpa = "0x88dc3417d5058ec4b4503e0c12ea1a0a89be200fe98922423d4334014fa6b0ee"
+        bond = 1000
+
+        [[endowed]]
+        account = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+        balance = 500
+    "#;
+
+    pub(crate) fn manifest() -> Manifest {
+        toml::from_str(MANIFEST).unwrap()
+    }
+
+    #[test]
+    fn bond_is_added_to_endowment() {
+        let patch = manifest().genesis_config_patch().unwrap();
+        let balances = patch["balances"]["balances"].as_array().unwrap();
+
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0][1], 1500);
+        assert_eq!(patch["parachainInfo"]["parachainId"], 2000);
+        assert_eq!(patch["relayChainSlot"]["slotDurationMillis"], 6000);
+        assert_eq!(patch["collatorSelection"]["candidacyBond"], 100);
+    }
+
+    #[test]
+    fn rejects_overflowing_endowments() {
+        let mut manifest = manifest();
+        manifest.endowed[0].balance = Balance::MAX;
+
+        assert!(matches!(
+            manifest.genesis_config_patch(),
+            Err(ManifestError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn reads_json_manifests() {
+        let manifest: serde_json::Value = toml::from_str(MANIFEST).unwrap();
+        let path = std::env::temp_dir().join(format!("manifest-{}.json", std::process::id()));
+        std::fs::write(&path, manifest.to_string()).unwrap();
+
+        let manifest = Manifest::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let patch = manifest.unwrap().genesis_config_patch().unwrap();
+        assert_eq!(patch["balances"]["balances"][0][1], 1500);
+        assert_eq!(patch["collatorSelection"]["candidacyBond"], 100);
+    }
+
+    #[test]
+    fn vesting_schedules_are_added_to_patch() {
+        let mut manifest = manifest();
+        manifest.endowed[0].vesting = Some(Vesting {
+            begin: 10,
+            length: 100,
+            liquid: 50,
+        });
+
+        let patch = manifest.genesis_config_patch().unwrap();
+        let vesting = patch["vesting"]["vesting"].as_array().unwrap();
+        assert_eq!(vesting.len(), 1);
+        assert_eq!(vesting[0][1], 10);
+        assert_eq!(vesting[0][2], 100);
+        assert_eq!(vesting[0][3], 50);
+
+        manifest.endowed[0].vesting = Some(Vesting {
+            begin: 10,
+            length: 100,
+            liquid: 501,
+        });
+        assert!(matches!(
+            manifest.genesis_config_patch(),
+            Err(ManifestError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_keys() {
+        let mut manifest = manifest();
+        manifest.validators[0].grandpa = "0x88dc34".into();
+
+        let err = manifest.genesis_config_patch().unwrap_err();
+        assert!(matches!(
+            err,
+            ManifestError::InvalidKey { ref field, .. } if field == "validators[0].grandpa"
+        ));
+
+        let mut manifest = self::manifest();
+        manifest.validators[0].babe = "not an address".into();
+        assert!(matches!(
+            manifest.genesis_config_patch(),
+            Err(ManifestError::InvalidKey { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_duplicate_validators() {
+        let mut manifest = manifest();
+        let duplicate = Validator {
+            stash: manifest.validators[0].stash.clone(),
+            babe: manifest.validators[0].babe.clone(),
+            grandpa: manifest.validators[0].grandpa.clone(),
+            bond: 1,
+        };
+        manifest.validators.push(duplicate);
+
+        assert!(matches!(
+            manifest.genesis_config_patch(),
+            Err(ManifestError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let manifest = format!("{}\nbootnodes = []\n", MANIFEST);
+        assert!(toml::from_str::<Manifest>(&manifest).is_err());
+    }
+}
@@ -0,0 +1,266 @@
+//! Prometheus metrics describing how this node takes part in BABE, GRANDPA and session rotation.
+//!
+//! The standard Substrate metrics only cover block import and networking. The metrics here are
+//! updated on every new best block and are meant to back operator alerts, e.g. on a growing
+//! finality lag or on local session keys dropping out of the active set.
+
+use std::sync::Arc;
+
+use codec::Decode;
+use futures::StreamExt;
+use parachain_template_runtime::opaque::Block;
+use sc_client_api::{Backend, BlockchainEvents, StorageKey, StorageProvider};
+use sc_consensus_babe::{authorship::claim_slot_using_keys, find_pre_digest, BabeWorkerHandle};
+use sc_consensus_grandpa::SharedVoterState;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_babe::{digests::PreDigest, AuthorityId as BabeId, BabeApi};
+use sp_consensus_grandpa::GrandpaApi;
+use sp_core::{crypto::ByteArray, hashing::twox_128};
+use sp_keystore::KeystorePtr;
+use sp_runtime::traits::{Header as HeaderT, SaturatedConversion, Zero};
+use substrate_prometheus_endpoint::{register, Counter, Gauge, PrometheusError, Registry, U64};
+
+/// Log target of this module.
+const LOG_TARGET: &str = "npos-metrics";
+
+/// Consensus and session metrics of the local node.
+#[derive(Clone)]
+pub struct Metrics {
+    babe_epoch_index: Gauge<U64>,
+    babe_slot: Gauge<U64>,
+    babe_primary_claims: Counter<U64>,
+    babe_secondary_claims: Counter<U64>,
+    babe_missed_slots: Counter<U64>,
+    babe_is_authority: Gauge<U64>,
+    grandpa_round: Gauge<U64>,
+    grandpa_finality_lag: Gauge<U64>,
+    grandpa_is_voter: Gauge<U64>,
+    session_index: Gauge<U64>,
+}
+
+impl Metrics {
+    /// Create the metrics and register them with the given `registry`.
+    pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            babe_epoch_index: register(
+                Gauge::new(
+                    "npos_babe_epoch_index",
+                    "Index of the BABE epoch of the best block",
+                )?,
+                registry,
+            )?,
+            babe_slot: register(
+                Gauge::new("npos_babe_slot", "BABE slot of the best block")?,
+                registry,
+            )?,
+            babe_primary_claims: register(
+                Counter::new(
+                    "npos_babe_primary_claims_total",
+                    "Best blocks authored by local keys through a primary slot claim",
+                )?,
+                registry,
+            )?,
+            babe_secondary_claims: register(
+                Counter::new(
+                    "npos_babe_secondary_claims_total",
+                    "Best blocks authored by local keys through a secondary slot claim",
+                )?,
+                registry,
+            )?,
+            babe_missed_slots: register(
+                Counter::new(
+                    "npos_babe_missed_slots_total",
+                    "Empty slots which local keys were eligible to claim",
+                )?,
+                registry,
+            )?,
+            babe_is_authority: register(
+                Gauge::new(
+                    "npos_babe_is_authority",
+                    "Whether a local BABE key is in the authority set of the current epoch",
+                )?,
+                registry,
+            )?,
+            grandpa_round: register(
+                Gauge::new(
+                    "npos_grandpa_round",
+                    "Best GRANDPA round of the local voter",
+                )?,
+                registry,
+            )?,
+            grandpa_finality_lag: register(
+                Gauge::new(
+                    "npos_grandpa_finality_lag",
+                    "Number of blocks between the best and the finalized block",
+                )?,
+                registry,
+            )?,
+            grandpa_is_voter: register(
+                Gauge::new(
+                    "npos_grandpa_is_voter",
+                    "Whether a local GRANDPA key is in the current voter set",
+                )?,
+                registry,
+            )?,
+            session_index: register(
+                Gauge::new("npos_session_index", "Current session index")?,
+                registry,
+            )?,
+        })
+    }
+}
+
+/// Build the storage key of a plain storage value.
+fn storage_value_key(pallet: &[u8], item: &[u8]) -> StorageKey {
+    StorageKey([twox_128(pallet), twox_128(item)].concat())
+}
+
+/// Read and decode a plain storage value at the given block.
+fn read_storage_value<C, BE, T: Decode>(
+    client: &C,
+    hash: <Block as sp_runtime::traits::Block>::Hash,
+    pallet: &[u8],
+    item: &[u8],
+) -> Option<T>
+where
+    C: StorageProvider<Block, BE>,
+    BE: Backend<Block>,
+{
+    client
+        .storage(hash, &storage_value_key(pallet, item))
+        .ok()
+        .flatten()
+        .and_then(|data| T::decode(&mut &data.0[..]).ok())
+}
+
+/// Keep the [`Metrics`] up to date by following the best block of `client`.
+///
+/// The future only resolves once the import notification stream of the client is closed.
+pub async fn run<C, BE>(
+    client: Arc<C>,
+    keystore: KeystorePtr,
+    babe_worker_handle: BabeWorkerHandle<Block>,
+    shared_voter_state: SharedVoterState,
+    metrics: Metrics,
+) where
+    C: BlockchainEvents<Block>
+        + HeaderBackend<Block>
+        + StorageProvider<Block, BE>
+        + ProvideRuntimeApi<Block>
+        + Send
+        + Sync
+        + 'static,
+    C::Api: BabeApi<Block> + GrandpaApi<Block>,
+    BE: Backend<Block>,
+{
+    let mut import_notifications = client.import_notification_stream();
+
+    while let Some(notification) = import_notifications.next().await {
+        if !notification.is_new_best {
+            continue;
+        }
+
+        let header = notification.header;
+        let hash = notification.hash;
+
+        let info = client.info();
+        metrics.grandpa_finality_lag.set(
+            info.best_number
+                .saturating_sub(info.finalized_number)
+                .saturated_into(),
+        );
+
+        if let Some(voter_state) = shared_voter_state.voter_state() {
+            metrics.grandpa_round.set(voter_state.best_round.0);
+        }
+
+        if let Some(session_index) =
+            read_storage_value::<_, _, u32>(&*client, hash, b"Session", b"CurrentIndex")
+        {
+            metrics.session_index.set(session_index.into());
+        }
+
+        let grandpa_authorities = client
+            .runtime_api()
+            .grandpa_authorities(hash)
+            .unwrap_or_default();
+        let is_voter = grandpa_authorities
+            .iter()
+            .any(|(id, _)| keystore.has_keys(&[(id.to_raw_vec(), sp_consensus_grandpa::KEY_TYPE)]));
+        metrics.grandpa_is_voter.set(is_voter as u64);
+
+        if let Err(err) =
+            update_babe_metrics(&*client, &keystore, &babe_worker_handle, &metrics, &header).await
+        {
+            log::debug!(target: LOG_TARGET, "Failed to update BABE metrics: {}", err);
+        }
+    }
+}
+
+/// Update the BABE related metrics for a new best block.
+async fn update_babe_metrics<C>(
+    client: &C,
+    keystore: &KeystorePtr,
+    babe_worker_handle: &BabeWorkerHandle<Block>,
+    metrics: &Metrics,
+    header: &<Block as sp_runtime::traits::Block>::Header,
+) -> Result<(), String>
+where
+    C: HeaderBackend<Block>,
+{
+    let pre_digest = find_pre_digest::<Block>(header).map_err(|e| e.to_string())?;
+    let slot = pre_digest.slot();
+
+    let parent = client
+        .header(*header.parent_hash())
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Parent header not found".to_string())?;
+
+    let epoch = babe_worker_handle
+        .epoch_data_for_child_of(parent.hash(), *parent.number(), slot)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    metrics.babe_epoch_index.set(epoch.epoch_index);
+    metrics.babe_slot.set(*slot);
+
+    let local_keys: Vec<(BabeId, usize)> = epoch
+        .authorities
+        .iter()
+        .enumerate()
+        .filter(|(_, (id, _))| keystore.has_keys(&[(id.to_raw_vec(), sp_consensus_babe::KEY_TYPE)]))
+        .map(|(index, (id, _))| (id.clone(), index))
+        .collect();
+    metrics.babe_is_authority.set(!local_keys.is_empty() as u64);
+
+    if local_keys.is_empty() {
+        return Ok(());
+    }
+
+    let authored_locally = local_keys
+        .iter()
+        .any(|(_, index)| *index == pre_digest.authority_index() as usize);
+    if authored_locally {
+        match pre_digest {
+            PreDigest::Primary(_) => metrics.babe_primary_claims.inc(),
+            PreDigest::SecondaryPlain(_) | PreDigest::SecondaryVRF(_) => {
+                metrics.babe_secondary_claims.inc()
+            }
+        }
+    }
+
+    // Every slot between the parent and this block stayed empty. Count the ones we could have
+    // claimed. The genesis block does not carry a slot, so there is nothing to compare against.
+    if !parent.number().is_zero() {
+        let parent_slot = find_pre_digest::<Block>(&parent)
+            .map_err(|e| e.to_string())?
+            .slot();
+        let missed = (*parent_slot + 1..*slot)
+            .filter(|s| claim_slot_using_keys((*s).into(), &epoch, keystore, &local_keys).is_some())
+            .count();
+        metrics.babe_missed_slots.inc_by(missed as u64);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,374 @@
+//! A scripted relay chain for running the node in-process.
+//!
+//! [`MockRelayChain`] implements [`RelayChainInterface`] on top of relay blocks the test imports
+//! itself. Every block includes a parachain head chosen by the test and carries a state proof that
+//! the runtime accepts, built with the relay state proof builder. Messages the node sends to the
+//! overseer, collations among them, are handed to the test through
+//! [`MockRelayChain::overseer_messages`].
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use codec::Encode;
+use cumulus_primitives_core::{
+    relay_chain::{
+        well_known_keys, CommittedCandidateReceipt, Hash as PHash, HeadData, Header as PHeader,
+        InboundHrmpMessage, OccupiedCoreAssumption, SessionIndex, ValidationCodeHash, ValidatorId,
+    },
+    InboundDownwardMessage, ParaId, PersistedValidationData,
+};
+use cumulus_relay_chain_interface::{
+    BlockId, OverseerHandle, RelayChainError, RelayChainInterface, RelayChainResult,
+};
+use cumulus_test_relay_sproof_builder::RelayStateSproofBuilder;
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    Stream, StreamExt,
+};
+use polkadot_node_subsystem::messages::AllMessages;
+use polkadot_overseer::Event as OverseerEvent;
+use sp_runtime::traits::Header as HeaderT;
+use sp_state_machine::StorageProof;
+use sp_version::RuntimeVersion;
+
+/// Slot duration of the mocked relay chain.
+const RELAY_SLOT_DURATION_MILLIS: u64 = 6000;
+
+/// A relay chain block with everything the node may ask about it.
+struct RelayBlock {
+    header: PHeader,
+    storage: BTreeMap<Vec<u8>, Vec<u8>>,
+    validation_data: PersistedValidationData,
+    state_proof: StorageProof,
+}
+
+#[derive(Default)]
+struct State {
+    blocks: HashMap<PHash, RelayBlock>,
+    best: Option<PHash>,
+    finalized: Option<PHash>,
+    import_sinks: Vec<UnboundedSender<PHeader>>,
+    best_sinks: Vec<UnboundedSender<PHeader>>,
+    finality_sinks: Vec<UnboundedSender<PHeader>>,
+}
+
+impl State {
+    fn block(&self, hash: PHash) -> RelayChainResult<&RelayBlock> {
+        self.blocks
+            .get(&hash)
+            .ok_or_else(|| RelayChainError::GenericError(format!("Unknown relay block {}", hash)))
+    }
+
+    fn best(&self) -> RelayChainResult<&RelayBlock> {
+        let best = self
+            .best
+            .ok_or_else(|| RelayChainError::GenericError("No relay block imported yet".into()))?;
+        self.block(best)
+    }
+}
+
+/// Send `header` to all live subscribers in `sinks`.
+fn notify(sinks: &mut Vec<UnboundedSender<PHeader>>, header: &PHeader) {
+    sinks.retain(|sink| sink.unbounded_send(header.clone()).is_ok());
+}
+
+/// A relay chain whose blocks are imported by hand.
+#[derive(Clone)]
+pub struct MockRelayChain {
+    para_id: ParaId,
+    state: Arc<Mutex<State>>,
+    overseer_handle: OverseerHandle,
+    overseer_messages: Arc<Mutex<Option<UnboundedReceiver<AllMessages>>>>,
+}
+
+impl MockRelayChain {
+    /// A relay chain without blocks that the parachain `para_id` is registered on.
+    pub fn new(para_id: ParaId) -> Self {
+        let (sender, receiver) = metered::channel::<OverseerEvent>(1024);
+        let (messages_sender, messages) = unbounded();
+        // Sending blocks once the channel is full, so keep forwarding the messages until the last
+        // handle is dropped. They are dropped once the test dropped their receiver.
+        std::thread::Builder::new()
+            .name("mock-overseer".into())
+            .spawn(move || {
+                futures::executor::block_on(receiver.for_each(|event| {
+                    if let OverseerEvent::MsgToSubsystem { msg, .. } = event {
+                        let _ = messages_sender.unbounded_send(msg);
+                    }
+                    async {}
+                }))
+            })
+            .expect("the OS can spawn a thread");
+
+        Self {
+            para_id,
+            state: Default::default(),
+            overseer_handle: OverseerHandle::new(sender),
+            overseer_messages: Arc::new(Mutex::new(Some(messages))),
+        }
+    }
+
+    /// The messages the node sends to the overseer, in the order they were sent.
+    ///
+    /// There is one receiver for all clones of the relay chain, only the first call gets it.
+    pub fn overseer_messages(&self) -> UnboundedReceiver<AllMessages> {
+        self.overseer_messages
+            .lock()
+            .expect("no test panicked with the lock held")
+            .take()
+            .expect("the overseer messages are only taken once")
+    }
+
+    /// Import a new best relay block on top of the current best, which includes `para_head`.
+    ///
+    /// The relay slot is taken from the system clock, like the one of the parachain is.
+    pub fn import_block(&self, para_head: HeadData) -> PHash {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the clock is past the unix epoch")
+            .as_millis() as u64;
+        let slot = now / RELAY_SLOT_DURATION_MILLIS;
+
+        let mut state = self
+            .state
+            .lock()
+            .expect("no test panicked with the lock held");
+        let (number, parent_hash) = match state.best() {
+            Ok(best) => (best.header.number + 1, best.header.hash()),
+            Err(_) => (0, PHash::default()),
+        };
+
+        let sproof = RelayStateSproofBuilder {
+            para_id: self.para_id,
+            current_slot: slot.into(),
+            included_para_head: Some(para_head.clone()),
+            ..Default::default()
+        };
+        let (state_root, state_proof) = sproof.into_state_root_and_proof();
+
+        let storage = BTreeMap::from([
+            (well_known_keys::CURRENT_SLOT.to_vec(), slot.encode()),
+            (
+                [sp_core::twox_128(b"Timestamp"), sp_core::twox_128(b"Now")].concat(),
+                (slot * RELAY_SLOT_DURATION_MILLIS).encode(),
+            ),
+        ]);
+        let validation_data = PersistedValidationData {
+            parent_head: para_head,
+            relay_parent_number: number,
+            relay_parent_storage_root: state_root,
+            max_pov_size: 5 * 1024 * 1024,
+        };
+        let header = PHeader::new(
+            number,
+            Default::default(),
+            state_root,
+            parent_hash,
+            Default::default(),
+        );
+        let hash = header.hash();
+
+        state.blocks.insert(
+            hash,
+            RelayBlock {
+                header: header.clone(),
+                storage,
+                validation_data,
+                state_proof,
+            },
+        );
+        state.best = Some(hash);
+        notify(&mut state.import_sinks, &header);
+        notify(&mut state.best_sinks, &header);
+
+        hash
+    }
+
+    /// Finalize the relay block `hash`, and with it the parachain head it includes.
+    pub fn finalize(&self, hash: PHash) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("no test panicked with the lock held");
+        let header = state
+            .blocks
+            .get(&hash)
+            .expect("only imported blocks are finalized")
+            .header
+            .clone();
+        state.finalized = Some(hash);
+        notify(&mut state.finality_sinks, &header);
+    }
+
+    fn stream(
+        &self,
+        sinks: impl FnOnce(&mut State) -> &mut Vec<UnboundedSender<PHeader>>,
+    ) -> Pin<Box<dyn Stream<Item = PHeader> + Send>> {
+        let (sender, receiver) = unbounded();
+        let mut state = self
+            .state
+            .lock()
+            .expect("no test panicked with the lock held");
+        sinks(&mut state).push(sender);
+        Box::pin(receiver)
+    }
+}
+
+#[async_trait::async_trait]
+impl RelayChainInterface for MockRelayChain {
+    async fn get_storage_by_key(
+        &self,
+        relay_parent: PHash,
+        key: &[u8],
+    ) -> RelayChainResult<Option<Vec<u8>>> {
+        let state = self
+            .state
+            .lock()
+            .expect("no test panicked with the lock held");
+        Ok(state.block(relay_parent)?.storage.get(key).cloned())
+    }
+
+    async fn validators(&self, _: PHash) -> RelayChainResult<Vec<ValidatorId>> {
+        Ok(Vec::new())
+    }
+
+    async fn best_block_hash(&self) -> RelayChainResult<PHash> {
+        let state = self
+            .state
+            .lock()
+            .expect("no test panicked with the lock held");
+        Ok(state.best()?.header.hash())
+    }
+
+    async fn finalized_block_hash(&self) -> RelayChainResult<PHash> {
+        let state = self
+            .state
+            .lock()
+            .expect("no test panicked with the lock held");
+        state
+            .finalized
+            .ok_or_else(|| RelayChainError::GenericError("No relay block finalized yet".into()))
+    }
+
+    async fn retrieve_dmq_contents(
+        &self,
+        _: ParaId,
+        _: PHash,
+    ) -> RelayChainResult<Vec<InboundDownwardMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn retrieve_all_inbound_hrmp_channel_contents(
+        &self,
+        _: ParaId,
+        _: PHash,
+    ) -> RelayChainResult<BTreeMap<ParaId, Vec<InboundHrmpMessage>>> {
+        Ok(BTreeMap::new())
+    }
+
+    async fn persisted_validation_data(
+        &self,
+        relay_parent: PHash,
+        para_id: ParaId,
+        _: OccupiedCoreAssumption,
+    ) -> RelayChainResult<Option<PersistedValidationData>> {
+        let state = self
+            .state
+            .lock()
+            .expect("no test panicked with the lock held");
+        let block = state.block(relay_parent)?;
+        Ok((para_id == self.para_id).then(|| block.validation_data.clone()))
+    }
+
+    async fn validation_code_hash(
+        &self,
+        _: PHash,
+        _: ParaId,
+        _: OccupiedCoreAssumption,
+    ) -> RelayChainResult<Option<ValidationCodeHash>> {
+        Ok(None)
+    }
+
+    async fn candidate_pending_availability(
+        &self,
+        _: PHash,
+        _: ParaId,
+    ) -> RelayChainResult<Option<CommittedCandidateReceipt>> {
+        Ok(None)
+    }
+
+    async fn session_index_for_child(&self, _: PHash) -> RelayChainResult<SessionIndex> {
+        Ok(0)
+    }
+
+    async fn import_notification_stream(
+        &self,
+    ) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+        Ok(self.stream(|state| &mut state.import_sinks))
+    }
+
+    async fn new_best_notification_stream(
+        &self,
+    ) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+        Ok(self.stream(|state| &mut state.best_sinks))
+    }
+
+    async fn finality_notification_stream(
+        &self,
+    ) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+        Ok(self.stream(|state| &mut state.finality_sinks))
+    }
+
+    async fn wait_for_block(&self, hash: PHash) -> RelayChainResult<()> {
+        let state = self
+            .state
+            .lock()
+            .expect("no test panicked with the lock held");
+        state.block(hash).map(|_| ())
+    }
+
+    async fn is_major_syncing(&self) -> RelayChainResult<bool> {
+        Ok(false)
+    }
+
+    fn overseer_handle(&self) -> RelayChainResult<OverseerHandle> {
+        Ok(self.overseer_handle.clone())
+    }
+
+    async fn prove_read(
+        &self,
+        relay_parent: PHash,
+        _: &Vec<Vec<u8>>,
+    ) -> RelayChainResult<StorageProof> {
+        // The proof holds the whole relay state the runtime may read, which is a superset of
+        // whatever keys were asked for.
+        let state = self
+            .state
+            .lock()
+            .expect("no test panicked with the lock held");
+        Ok(state.block(relay_parent)?.state_proof.clone())
+    }
+
+    async fn header(&self, block_id: BlockId) -> RelayChainResult<Option<PHeader>> {
+        let state = self
+            .state
+            .lock()
+            .expect("no test panicked with the lock held");
+        Ok(match block_id {
+            BlockId::Hash(hash) => state.blocks.get(&hash).map(|block| block.header.clone()),
+            BlockId::Number(number) => state
+                .blocks
+                .values()
+                .find(|block| block.header.number == number)
+                .map(|block| block.header.clone()),
+        })
+    }
+
+    async fn version(&self, _: PHash) -> RelayChainResult<RuntimeVersion> {
+        Ok(RuntimeVersion::default())
+    }
+}
@@ -5,18 +5,28 @@
 
 #![warn(missing_docs)]
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    proc_macros::rpc,
+    types::ErrorObject,
+};
 use parachain_template_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, Nonce};
+use serde::{Deserialize, Serialize};
 
-use sc_client_api::backend::AuxStore;
+use futures::StreamExt;
+use sc_client_api::{backend::AuxStore, BlockchainEvents};
 use sc_consensus_babe::BabeWorkerHandle;
 use sc_consensus_grandpa::{
     FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet, SharedVoterState,
 };
-use sp_consensus::SelectChain;
-use sp_consensus_babe::BabeApi;
+use sc_network_sync::SyncingService;
+use sp_consensus::{BlockOrigin, SelectChain, SyncOracle};
+use sp_consensus_babe::{BabeApi, Slot};
+use sp_core::crypto::ByteArray;
 use sp_keystore::KeystorePtr;
+use sp_runtime::traits::Header as HeaderT;
 
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
 use sc_transaction_pool_api::TransactionPool;
@@ -33,6 +43,8 @@ pub struct BabeDeps {
     pub babe_worker_handle: BabeWorkerHandle<Block>,
     /// The keystore that manages the keys of the node.
     pub keystore: KeystorePtr,
+    /// The last block authored by the node.
+    pub last_authored_block: LastAuthoredBlock,
 }
 
 /// Extra dependencies for GRANDPA
@@ -62,6 +74,8 @@ pub struct FullDeps<C, P, SC, B> {
     pub select_chain: SC,
     /// A copy of the chain spec.
     pub chain_spec: Box<dyn sc_chain_spec::ChainSpec>,
+    /// The syncing service, to report whether the node is syncing.
+    pub sync_service: Arc<SyncingService<Block>>,
     /// BABE specific dependencies
     pub babe: BabeDeps,
     /// GRANDPA specific dependencies
@@ -103,6 +117,7 @@ where
         deny_unsafe,
         select_chain,
         chain_spec,
+        sync_service,
         babe,
         grandpa,
     } = deps;
@@ -110,6 +125,7 @@ where
     let BabeDeps {
         babe_worker_handle,
         keystore,
+        last_authored_block,
     } = babe;
     let GrandpaDeps {
         shared_voter_state,
@@ -130,6 +146,18 @@ where
     module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
     module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
 
+    module.merge(
+        NposHealth::new(
+            client.clone(),
+            sync_service,
+            babe_worker_handle.clone(),
+            keystore.clone(),
+            shared_authority_set.clone(),
+            last_authored_block,
+            deny_unsafe,
+        )
+        .into_rpc(),
+    )?;
     module.merge(
         Babe::new(
             client.clone(),
@@ -153,3 +181,197 @@ where
 
     Ok(module)
 }
+
+/// The last block the node authored, reported by `npos_health`.
+///
+/// Tracked from block import, so the RPC doesn't walk the chain to find it.
+#[derive(Clone, Default)]
+pub struct LastAuthoredBlock(Arc<Mutex<Option<BlockNumber>>>);
+
+impl LastAuthoredBlock {
+    /// The number of the last block the node authored, if any since it started.
+    pub fn get(&self) -> Option<BlockNumber> {
+        *self.0.lock().expect("lock is never poisoned")
+    }
+
+    /// Record the blocks the node authors by following the import notifications of `client`.
+    ///
+    /// The future only resolves once the import notification stream of the client is closed.
+    pub async fn track<C>(self, client: Arc<C>)
+    where
+        C: BlockchainEvents<Block>,
+    {
+        let mut import_notifications = client.import_notification_stream();
+
+        while let Some(notification) = import_notifications.next().await {
+            if notification.origin == BlockOrigin::Own && notification.is_new_best {
+                *self.0.lock().expect("lock is never poisoned") =
+                    Some(*notification.header.number());
+            }
+        }
+    }
+}
+
+/// Summary of how the node takes part in consensus, returned by `npos_health`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeHealth {
+    /// Whether the node is performing a major sync.
+    pub is_syncing: bool,
+    /// Number of the best block.
+    pub best_number: BlockNumber,
+    /// Number of the last finalized block.
+    pub finalized_number: BlockNumber,
+    /// Number of blocks between the best and the finalized block.
+    pub finality_lag: BlockNumber,
+    /// How the node takes part in consensus, left out on endpoints that deny unsafe methods.
+    pub participation: Option<ConsensusParticipation>,
+}
+
+/// The consensus roles the keys of the node hold, part of [`NodeHealth`].
+///
+/// They tell which node a validator runs on, so public endpoints don't report them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsensusParticipation {
+    /// Whether a local key is a BABE authority in the current epoch.
+    pub babe_authority_current_epoch: bool,
+    /// Whether a local key is a BABE authority in the next epoch.
+    pub babe_authority_next_epoch: bool,
+    /// Whether a local key is in the current GRANDPA voter set.
+    pub grandpa_voter: bool,
+    /// The most recent block the node authored since it started.
+    pub last_authored_block: Option<BlockNumber>,
+}
+
+/// Node health RPC methods.
+#[rpc(server)]
+pub trait NposHealthApi {
+    /// Summarise sync, finality and consensus participation of this node.
+    ///
+    /// The method is safe, so load balancers can call it on public endpoints. Those don't report
+    /// the consensus participation, which reveals the keys the node holds.
+    #[method(name = "npos_health")]
+    async fn health(&self) -> RpcResult<NodeHealth>;
+}
+
+/// Implements the [`NposHealthApiServer`] RPC trait.
+pub struct NposHealth<C> {
+    client: Arc<C>,
+    sync_service: Arc<SyncingService<Block>>,
+    babe_worker_handle: BabeWorkerHandle<Block>,
+    keystore: KeystorePtr,
+    shared_authority_set: SharedAuthoritySet<Hash, BlockNumber>,
+    last_authored_block: LastAuthoredBlock,
+    deny_unsafe: DenyUnsafe,
+}
+
+impl<C> NposHealth<C> {
+    /// Create a new instance of the health RPC handler.
+    pub fn new(
+        client: Arc<C>,
+        sync_service: Arc<SyncingService<Block>>,
+        babe_worker_handle: BabeWorkerHandle<Block>,
+        keystore: KeystorePtr,
+        shared_authority_set: SharedAuthoritySet<Hash, BlockNumber>,
+        last_authored_block: LastAuthoredBlock,
+        deny_unsafe: DenyUnsafe,
+    ) -> Self {
+        Self {
+            client,
+            sync_service,
+            babe_worker_handle,
+            keystore,
+            shared_authority_set,
+            last_authored_block,
+            deny_unsafe,
+        }
+    }
+
+    /// The consensus roles of the keys of the node at the best block.
+    async fn participation(
+        &self,
+        info: &sp_blockchain::Info<Block>,
+    ) -> RpcResult<ConsensusParticipation>
+    where
+        C: ProvideRuntimeApi<Block>,
+        C::Api: BabeApi<Block>,
+    {
+        let slot_duration = self
+            .client
+            .runtime_api()
+            .configuration(info.best_hash)
+            .map_err(health_error)?
+            .slot_duration();
+        let slot_now = Slot::from_timestamp(sp_timestamp::Timestamp::current(), slot_duration);
+
+        let current_epoch = self
+            .babe_worker_handle
+            .epoch_data_for_child_of(info.best_hash, info.best_number, slot_now)
+            .await
+            .map_err(health_error)?;
+        let next_epoch = self
+            .babe_worker_handle
+            .epoch_data_for_child_of(
+                info.best_hash,
+                info.best_number,
+                current_epoch.start_slot + current_epoch.duration,
+            )
+            .await
+            .map_err(health_error)?;
+
+        let grandpa_voter = self
+            .shared_authority_set
+            .current_authorities()
+            .iter()
+            .any(|voter| {
+                self.keystore
+                    .has_keys(&[(voter.0.to_raw_vec(), sp_consensus_grandpa::KEY_TYPE)])
+            });
+
+        Ok(ConsensusParticipation {
+            babe_authority_current_epoch: self.has_babe_key(&current_epoch.authorities),
+            babe_authority_next_epoch: self.has_babe_key(&next_epoch.authorities),
+            grandpa_voter,
+            last_authored_block: self.last_authored_block.get(),
+        })
+    }
+
+    /// Whether the keystore holds the key of any of the given BABE authorities.
+    fn has_babe_key(&self, authorities: &[(sp_consensus_babe::AuthorityId, u64)]) -> bool {
+        authorities.iter().any(|(id, _)| {
+            self.keystore
+                .has_keys(&[(id.to_raw_vec(), sp_consensus_babe::KEY_TYPE)])
+        })
+    }
+}
+
+/// Error code returned if the health can't be determined.
+const HEALTH_ERROR: i32 = 9100;
+
+fn health_error(message: impl ToString) -> ErrorObject<'static> {
+    ErrorObject::owned(HEALTH_ERROR, message.to_string(), None::<()>)
+}
+
+#[async_trait]
+impl<C> NposHealthApiServer for NposHealth<C>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: BabeApi<Block>,
+{
+    async fn health(&self) -> RpcResult<NodeHealth> {
+        let info = self.client.info();
+        let participation = match self.deny_unsafe.check_if_safe() {
+            Ok(()) => Some(self.participation(&info).await?),
+            Err(_) => None,
+        };
+
+        Ok(NodeHealth {
+            is_syncing: self.sync_service.is_major_syncing(),
+            best_number: info.best_number,
+            finalized_number: info.finalized_number,
+            finality_lag: info.best_number.saturating_sub(info.finalized_number),
+            participation,
+        })
+    }
+}
@@ -1,40 +1,54 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 // std
-use sc_client_api::{Backend, BlockBackend};
+use codec::{Decode, Encode};
+use sc_client_api::{Backend, BlockBackend, StorageKey, StorageProvider};
 use std::{sync::Arc, time::Duration};
 
 use cumulus_client_cli::CollatorOptions;
 // Local Runtime Types
 use parachain_template_runtime::{opaque::Block, RuntimeApi};
 // Cumulus Imports
-// use cumulus_client_collator::service::CollatorService;
+use cumulus_client_collator::service::CollatorService;
 use cumulus_client_consensus_common::ParachainBlockImport as TParachainBlockImport;
 // use cumulus_client_consensus_proposer::Proposer;
+use cumulus_client_parachain_inherent::{ParachainInherentData, ParachainInherentDataProvider};
 use cumulus_client_service::{
-    build_network, build_relay_chain_interface, prepare_node_config, start_relay_chain_tasks,
-    BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile, StartRelayChainTasksParams,
+    build_relay_chain_interface, prepare_node_config, start_relay_chain_tasks, warp_sync_get,
+    DARecoveryProfile, StartRelayChainTasksParams,
+};
+use cumulus_primitives_core::{
+    relay_chain::{well_known_keys, CollatorPair, HeadData, OccupiedCoreAssumption},
+    ParaId,
 };
-use cumulus_primitives_core::ParaId;
 use cumulus_relay_chain_interface::RelayChainInterface;
 use sc_transaction_pool_api::{OffchainTransactionPoolFactory, RejectAllTxPool};
 
 // Substrate Imports
-use crate::rpc::{create_full, BabeDeps, FullDeps, GrandpaDeps};
+use crate::{
+    block_announce::BabeBlockAnnounceValidator,
+    rpc::{create_full, BabeDeps, FullDeps, GrandpaDeps, LastAuthoredBlock},
+};
 use frame_benchmarking_cli::SUBSTRATE_REFERENCE_HARDWARE;
 use sc_consensus::ImportQueue;
 use sc_consensus_babe::SlotProportion;
 use sc_executor::{
     HeapAllocStrategy, NativeElseWasmExecutor, WasmExecutor, DEFAULT_HEAP_ALLOC_STRATEGY,
 };
-use sc_network::NetworkBlock;
+use sc_network::{config::SyncMode, NetworkBlock};
+use sc_network_sync::{warp::WarpSyncParams, SyncingService};
 use sc_service::{Configuration, PartialComponents, TFullBackend, TFullClient, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker, TelemetryWorkerHandle};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
 
 /// The minimum period of blocks on which justifications will be
 /// imported and generated.
 const GRANDPA_JUSTIFICATION_PERIOD: u32 = 512;
 
+/// Relay chain slot duration assumed when it can't be derived from the relay chain.
+const DEFAULT_RELAY_CHAIN_SLOT_DURATION: Duration = Duration::from_secs(6);
+
 /// Native executor type.
 pub struct ParachainNativeExecutor;
 
@@ -75,6 +89,8 @@ type FullGrandpaLink = sc_consensus_grandpa::LinkHalf<Block, ParachainClient, Fu
 type FullGrandpaSharedVoterState = sc_consensus_grandpa::SharedVoterState;
 // State that must be shared between the import queue and the authoring logic.
 type FullBabeLink = sc_consensus_babe::BabeLink<Block>;
+// Handle to the BABE worker for querying epoch data.
+type FullBabeWorkerHandle = sc_consensus_babe::BabeWorkerHandle<Block>;
 
 /// Assembly of PartialComponents (enough to run chain ops subcommands)
 pub type Service<TRpcBuilder> = PartialComponents<
@@ -86,7 +102,12 @@ pub type Service<TRpcBuilder> = PartialComponents<
     (
         ParachainBlockImport,
         TRpcBuilder,
-        (FullBabeBlockImport, FullGrandpaLink, FullBabeLink),
+        (
+            FullBabeBlockImport,
+            FullGrandpaLink,
+            FullBabeLink,
+            FullBabeWorkerHandle,
+        ),
         FullGrandpaSharedVoterState,
         Option<Telemetry>,
         Option<TelemetryWorkerHandle>,
@@ -104,6 +125,7 @@ pub fn new_partial(
         impl Fn(
             sc_rpc::DenyUnsafe,
             sc_rpc::SubscriptionTaskExecutor,
+            Arc<SyncingService<Block>>,
         ) -> Result<jsonrpsee::RpcModule<()>, sc_service::Error>,
     >,
     sc_service::Error,
@@ -208,9 +230,14 @@ pub fn new_partial(
     let (import_queue, babe_worker_handle) =
         sc_consensus_babe::import_queue(babe_import_queue_params)?;
 
-    let import_setup = (babe_block_import, grandpa_link, babe_link);
+    let import_setup = (
+        babe_block_import,
+        grandpa_link,
+        babe_link,
+        babe_worker_handle.clone(),
+    );
     let (rpc_extensions_builder, rpc_setup) = {
-        let (_, grandpa_link, _babe_link) = &import_setup;
+        let (_, grandpa_link, _babe_link, _) = &import_setup;
 
         let justification_stream = grandpa_link.justification_stream();
         let shared_authority_set = grandpa_link.shared_authority_set().clone();
@@ -228,16 +255,25 @@ pub fn new_partial(
         let keystore = keystore_container.keystore();
         let chain_spec = config.chain_spec.cloned_box();
 
-        let rpc_extensions_builder = move |deny_unsafe, subscription_executor| {
+        let last_authored_block = LastAuthoredBlock::default();
+        task_manager.spawn_handle().spawn(
+            "npos-last-authored-block",
+            None,
+            last_authored_block.clone().track(client.clone()),
+        );
+
+        let rpc_extensions_builder = move |deny_unsafe, subscription_executor, sync_service| {
             let deps = FullDeps {
                 client: client.clone(),
                 pool: pool.clone(),
                 select_chain: select_chain.clone(),
                 chain_spec: chain_spec.cloned_box(),
+                sync_service,
                 deny_unsafe,
                 babe: BabeDeps {
                     babe_worker_handle: babe_worker_handle.clone(),
                     keystore: keystore.clone(),
+                    last_authored_block: last_authored_block.clone(),
                 },
                 grandpa: GrandpaDeps {
                     shared_voter_state: shared_voter_state.clone(),
@@ -273,14 +309,166 @@ pub fn new_partial(
     })
 }
 
-/// Start a node with the given parachain `Configuration` and relay chain `Configuration`.
+/// Derive the slot duration of the relay chain from the BABE state at its best block.
+///
+/// BABE numbers slots as `timestamp / slot_duration`, so dividing `Timestamp::Now` by
+/// `Babe::CurrentSlot` yields the slot duration without relying on a particular relay runtime.
+/// This fails while the relay chain is still at genesis.
+async fn fetch_relay_chain_slot_duration(
+    relay_chain_interface: &dyn RelayChainInterface,
+) -> Result<Duration, String> {
+    let best_hash = relay_chain_interface
+        .best_block_hash()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let current_slot = relay_chain_interface
+        .get_storage_by_key(best_hash, well_known_keys::CURRENT_SLOT)
+        .await
+        .map_err(|e| e.to_string())?
+        .and_then(|value| u64::decode(&mut &value[..]).ok())
+        .ok_or("Relay chain has no BABE slot")?;
+
+    let timestamp_key = [sp_core::twox_128(b"Timestamp"), sp_core::twox_128(b"Now")].concat();
+    let now = relay_chain_interface
+        .get_storage_by_key(best_hash, &timestamp_key)
+        .await
+        .map_err(|e| e.to_string())?
+        .and_then(|value| u64::decode(&mut &value[..]).ok())
+        .ok_or("Relay chain has no timestamp")?;
+
+    if current_slot == 0 {
+        return Err("Relay chain has not authored a block yet".into());
+    }
+
+    Ok(Duration::from_millis(now / current_slot))
+}
+
+/// Fail if the relay chain slot duration set in the chain spec does not match the relay chain.
+///
+/// The runtime converts relay chain slots with it, so block production would fail on every block.
+fn check_runtime_relay_chain_slot_duration(
+    client: &ParachainClient,
+    relay_chain_slot_duration: Duration,
+) -> Result<(), String> {
+    use parachain_template_runtime::{relay_chain_slot, Runtime};
+
+    let configured = client
+        .storage(
+            client.chain_info().best_hash,
+            &StorageKey(relay_chain_slot::SlotDurationMillis::<Runtime>::hashed_key().to_vec()),
+        )
+        .map_err(|e| e.to_string())?
+        .map(|value| u32::decode(&mut &value.0[..]).map_err(|e| e.to_string()))
+        .transpose()?
+        .unwrap_or(relay_chain_slot::DEFAULT_SLOT_DURATION_MILLIS);
+
+    if u128::from(configured) != relay_chain_slot_duration.as_millis() {
+        return Err(format!(
+            "The runtime assumes a relay chain slot duration of {}ms, but the relay chain uses {}ms. \
+             Set `relayChainSlot.slotDurationMillis` in the chain spec to match the relay chain.",
+            configured,
+            relay_chain_slot_duration.as_millis(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Warn if the node stores its state in a different database than the weights of the runtime on
+/// chain are for, as transactions would then be charged for the wrong database access costs.
+fn check_database_weights(client: &ParachainClient, database: &sc_service::config::DatabaseSource) {
+    use parachain_template_runtime::DbWeightApi;
+    use sc_service::config::DatabaseSource;
+    use sp_api::{ApiExt, ProvideRuntimeApi};
+
+    let backend = match database {
+        DatabaseSource::RocksDb { .. } => "rocksdb",
+        DatabaseSource::ParityDb { .. } => "paritydb",
+        DatabaseSource::Auto { .. } | DatabaseSource::Custom { .. } => return,
+    };
+
+    let best_hash = client.chain_info().best_hash;
+    let runtime_api = client.runtime_api();
+    match runtime_api.has_api::<dyn DbWeightApi<Block>>(best_hash) {
+        Ok(true) => {}
+        // Runtimes from before the API can't tell.
+        Ok(false) => return,
+        Err(err) => {
+            log::warn!(
+                "Failed to query the database weights of the runtime: {}",
+                err
+            );
+            return;
+        }
+    }
+    let runtime_backend = match runtime_api.db_weight_backend(best_hash) {
+        Ok(runtime_backend) => String::from_utf8_lossy(&runtime_backend).into_owned(),
+        Err(err) => {
+            log::warn!(
+                "Failed to query the database weights of the runtime: {}",
+                err
+            );
+            return;
+        }
+    };
+
+    if backend != runtime_backend {
+        log::warn!(
+            "⚠️  The node runs on {} but the runtime on chain charges {} weights for database \
+             accesses. Use `--database {}` to match.",
+            backend,
+            runtime_backend,
+            runtime_backend,
+        );
+    }
+}
+
+/// The parachain inherent of a block on top of `parent`, anchored at the best relay chain block.
+async fn parachain_inherent_data(
+    relay_chain_interface: &Arc<dyn RelayChainInterface>,
+    para_id: ParaId,
+    parent: &<Block as BlockT>::Header,
+) -> Result<ParachainInherentData, Box<dyn std::error::Error + Send + Sync>> {
+    let relay_parent = relay_chain_interface.best_block_hash().await?;
+    let mut validation_data = relay_chain_interface
+        .persisted_validation_data(relay_parent, para_id, OccupiedCoreAssumption::TimedOut)
+        .await?
+        .ok_or("The parachain is not registered on the relay chain")?;
+    // The relay chain only knows the included head, the block is built on our own best block.
+    validation_data.parent_head = HeadData(parent.encode());
+
+    ParachainInherentDataProvider::create_at(
+        relay_parent,
+        relay_chain_interface,
+        &validation_data,
+        para_id,
+    )
+    .await
+    .ok_or_else(|| "Failed to create the parachain inherent".into())
+}
+
+/// Where the node gets its view of the relay chain from.
+pub enum RelayChainSource {
+    /// An embedded relay chain node, or an RPC connection to one, as configured.
+    Node {
+        /// Configuration of the relay chain node.
+        polkadot_config: Configuration,
+        /// Options selecting the kind of relay chain interface.
+        collator_options: CollatorOptions,
+    },
+    /// An interface built by the caller, e.g. a mock relay chain in tests.
+    #[cfg_attr(not(test), allow(dead_code))]
+    Interface(Arc<dyn RelayChainInterface>),
+}
+
+/// Start a node with the given parachain `Configuration`, following the given relay chain.
 ///
 /// This is the actual implementation that is abstract over the executor and the runtime api.
 #[sc_tracing::logging::prefix_logs_with("Parachain")]
 async fn start_node_impl(
     parachain_config: Configuration,
-    polkadot_config: Configuration,
-    collator_options: CollatorOptions,
+    relay_chain: RelayChainSource,
     para_id: ParaId,
     hwbench: Option<sc_sysinfo::HwBench>,
 ) -> sc_service::error::Result<(TaskManager, Arc<ParachainClient>)> {
@@ -288,6 +476,7 @@ async fn start_node_impl(
 
     let params = new_partial(&parachain_config)?;
     let client = params.client.clone();
+    check_database_weights(&client, &parachain_config.database);
     let backend = params.backend.clone();
     let (
         _block_import,
@@ -317,23 +506,26 @@ async fn start_node_impl(
 
     net_config.add_notification_protocol(grandpa_protocol_config);
 
-    // There is no such fied warp sync in the cumulus network service
-    let _warp_sync = Arc::new(sc_consensus_grandpa::warp_proof::NetworkProvider::new(
-        backend.clone(),
-        import_setup.1.shared_authority_set().clone(),
-        Vec::default(),
-    ));
-
-    let (relay_chain_interface, _collator_key) = build_relay_chain_interface(
-        polkadot_config,
-        &parachain_config,
-        telemetry_worker_handle,
-        &mut task_manager,
-        collator_options.clone(),
-        hwbench.clone(),
-    )
-    .await
-    .map_err(|e| sc_service::Error::Application(Box::new(e) as Box<_>))?;
+    let (relay_chain_interface, collator_key) = match relay_chain {
+        RelayChainSource::Node {
+            polkadot_config,
+            collator_options,
+        } => build_relay_chain_interface(
+            polkadot_config,
+            &parachain_config,
+            telemetry_worker_handle,
+            &mut task_manager,
+            collator_options,
+            hwbench.clone(),
+        )
+        .await
+        .map_err(|e| sc_service::Error::Application(Box::new(e) as Box<_>))?,
+        // Without a relay chain node there is no collator key to take, any key will do.
+        RelayChainSource::Interface(relay_chain_interface) => (
+            relay_chain_interface,
+            Some(<CollatorPair as sp_core::Pair>::generate().0),
+        ),
+    };
 
     let role = parachain_config.role.clone();
     let force_authoring = parachain_config.force_authoring;
@@ -346,19 +538,38 @@ async fn start_node_impl(
     let transaction_pool = params.transaction_pool.clone();
     let import_queue_service = params.import_queue.service();
 
+    // BABE is sybil resistant on its own, as long as we only accept announcements of blocks
+    // sealed by an authority. The cumulus `build_network` doesn't let us plug in our own
+    // validator, so the network is built directly.
+    let block_announce_validator = BabeBlockAnnounceValidator::new(
+        client.clone(),
+        import_setup.3.clone(),
+        import_setup.2.config().slot_duration(),
+    );
+    // Warp sync the way cumulus `build_network` does: wait for the relay chain to tell us the
+    // parachain head to sync to.
+    let warp_sync_params = match parachain_config.network.sync_mode {
+        SyncMode::Warp => Some(WarpSyncParams::WaitForTarget(warp_sync_get::<Block, _>(
+            para_id,
+            relay_chain_interface.clone(),
+            task_manager.spawn_handle(),
+        ))),
+        _ => None,
+    };
     let (network, system_rpc_tx, tx_handler_controller, start_network, sync_service) =
-        build_network(BuildNetworkParams {
-            parachain_config: &parachain_config,
+        sc_service::build_network(sc_service::BuildNetworkParams {
+            config: &parachain_config,
             net_config,
             client: client.clone(),
             transaction_pool: transaction_pool.clone(),
-            para_id,
             spawn_handle: task_manager.spawn_handle(),
-            relay_chain_interface: relay_chain_interface.clone(),
             import_queue: params.import_queue,
-            sybil_resistance_level: CollatorSybilResistance::Resistant, // because of Aura
-        })
-        .await?;
+            block_announce_validator_builder: Some(Box::new(move |_| {
+                Box::new(block_announce_validator)
+            })),
+            warp_sync_params,
+            block_relay: None,
+        })?;
 
     if parachain_config.offchain_worker.enabled {
         use futures::FutureExt;
@@ -383,8 +594,15 @@ async fn start_node_impl(
         );
     }
 
+    let rpc_builder = {
+        let sync_service = sync_service.clone();
+        move |deny_unsafe, subscription_executor| {
+            rpc_extensions_builder(deny_unsafe, subscription_executor, sync_service.clone())
+        }
+    };
+
     sc_service::spawn_tasks(sc_service::SpawnTasksParams {
-        rpc_builder: Box::new(rpc_extensions_builder),
+        rpc_builder: Box::new(rpc_builder),
         client: client.clone(),
         transaction_pool: transaction_pool.clone(),
         task_manager: &mut task_manager,
@@ -398,7 +616,22 @@ async fn start_node_impl(
         telemetry: telemetry.as_mut(),
     })?;
 
-    let (babe_block_import, grandpa_link, babe_link) = import_setup;
+    let (babe_block_import, grandpa_link, babe_link, babe_worker_handle) = import_setup;
+
+    if let Some(registry) = prometheus_registry.as_ref() {
+        let metrics = crate::metrics::Metrics::register(registry)?;
+        task_manager.spawn_handle().spawn(
+            "npos-metrics",
+            None,
+            crate::metrics::run(
+                client.clone(),
+                params.keystore_container.keystore(),
+                babe_worker_handle,
+                shared_voter_state.clone(),
+                metrics,
+            ),
+        );
+    }
 
     if let sc_service::config::Role::Authority { .. } = &role {
         let proposer = sc_basic_authorship::ProposerFactory::new(
@@ -410,6 +643,7 @@ async fn start_node_impl(
         );
 
         let client_clone = client.clone();
+        let relay_chain_interface = relay_chain_interface.clone();
         let slot_duration = babe_link.config().slot_duration();
         let babe_config = sc_consensus_babe::BabeParams {
             keystore: params.keystore_container.keystore(),
@@ -421,6 +655,7 @@ async fn start_node_impl(
             justification_sync_link: sync_service.clone(),
             create_inherent_data_providers: move |parent, ()| {
                 let client_clone = client_clone.clone();
+                let relay_chain_interface = relay_chain_interface.clone();
                 async move {
                     let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
 
@@ -436,7 +671,14 @@ async fn start_node_impl(
                             &parent,
                         )?;
 
-                    Ok((slot, timestamp, storage_proof))
+                    let parent_header = client_clone
+                        .header(parent)?
+                        .ok_or("Parent header of the block to author not found")?;
+                    let parachain_inherent =
+                        parachain_inherent_data(&relay_chain_interface, para_id, &parent_header)
+                            .await?;
+
+                    Ok((slot, timestamp, storage_proof, parachain_inherent))
                 }
             },
             force_authoring,
@@ -534,7 +776,22 @@ async fn start_node_impl(
         Arc::new(move |hash, data| sync_service.announce_block(hash, data))
     };
 
-    let relay_chain_slot_duration = Duration::from_secs(6);
+    let relay_chain_slot_duration =
+        match fetch_relay_chain_slot_duration(&*relay_chain_interface).await {
+            Ok(duration) => {
+                check_runtime_relay_chain_slot_duration(&client, duration)
+                    .map_err(sc_service::Error::Other)?;
+                duration
+            }
+            Err(err) => {
+                log::warn!(
+                    "Could not derive the relay chain slot duration, assuming {:?}: {}",
+                    DEFAULT_RELAY_CHAIN_SLOT_DURATION,
+                    err,
+                );
+                DEFAULT_RELAY_CHAIN_SLOT_DURATION
+            }
+        };
 
     let overseer_handle = relay_chain_interface
         .overseer_handle()
@@ -557,6 +814,27 @@ async fn start_node_impl(
         sync_service: sync_service.clone(),
     })?;
 
+    if let Some(collator_key) = collator_key.filter(|_| validator) {
+        let collator_service = CollatorService::new(
+            client.clone(),
+            Arc::new(task_manager.spawn_handle()),
+            announce_block,
+            client.clone(),
+        );
+        task_manager.spawn_handle().spawn(
+            "collation-submission",
+            None,
+            crate::collation::run(
+                client.clone(),
+                collator_service,
+                relay_chain_interface.clone(),
+                overseer_handle,
+                collator_key,
+                para_id,
+            ),
+        );
+    }
+
     start_network.start_network();
 
     Ok((task_manager, client))
@@ -572,10 +850,195 @@ pub async fn start_parachain_node(
 ) -> sc_service::error::Result<(TaskManager, Arc<ParachainClient>)> {
     start_node_impl(
         parachain_config,
-        polkadot_config,
-        collator_options,
+        RelayChainSource::Node {
+            polkadot_config,
+            collator_options,
+        },
         para_id,
         hwbench,
     )
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block_announce::{check_header, AnnounceError},
+        chain_spec,
+        cli::Cli,
+        relay_chain_mock::MockRelayChain,
+    };
+    use clap::Parser;
+    use futures::StreamExt;
+    use polkadot_node_subsystem::messages::{AllMessages, CollationGenerationMessage};
+    use sc_cli::SubstrateCli;
+    use sc_consensus_babe::find_pre_digest;
+    use sp_api::ProvideRuntimeApi;
+    use sp_consensus_babe::BabeApi;
+    use sp_runtime::traits::Header as HeaderT;
+
+    /// How long the node gets to author and finalize blocks before a test fails.
+    const TIMEOUT: Duration = Duration::from_secs(120);
+
+    /// The configuration of a `--dev` collator, which authors blocks with Alice's keys, and its
+    /// para id.
+    fn dev_config(tokio_handle: tokio::runtime::Handle) -> (Configuration, ParaId) {
+        let cli = Cli::parse_from([
+            "parachain-template-node",
+            "--dev",
+            "--no-prometheus",
+            "--no-telemetry",
+            "--no-mdns",
+            "--port=0",
+            "--rpc-port=0",
+        ]);
+        let config = SubstrateCli::create_configuration(&cli, &cli.run.normalize(), tokio_handle)
+            .expect("the dev configuration is valid");
+        let para_id = chain_spec::Extensions::try_get(&*config.chain_spec)
+            .map(|e| e.para_id)
+            .expect("the dev chain spec has a para id");
+        (config, para_id.into())
+    }
+
+    /// Start a `--dev` collator following `relay_chain` and wait until it authored `blocks`.
+    ///
+    /// The mock relay chain imports a block including the collator's best block every second.
+    async fn author_blocks(
+        config: Configuration,
+        para_id: ParaId,
+        relay_chain: &MockRelayChain,
+        blocks: u32,
+    ) -> (TaskManager, Arc<ParachainClient>) {
+        let (task_manager, client) = start_node_impl(
+            config,
+            RelayChainSource::Interface(Arc::new(relay_chain.clone())),
+            para_id,
+            None,
+        )
+        .await
+        .expect("the node starts");
+
+        tokio::time::timeout(TIMEOUT, async {
+            while client.chain_info().best_number < blocks {
+                let best = client
+                    .header(client.chain_info().best_hash)
+                    .expect("the best header is readable")
+                    .expect("the best block exists");
+                relay_chain.import_block(HeadData(best.encode()));
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        })
+        .await
+        .expect("the collator authors blocks");
+
+        (task_manager, client)
+    }
+
+    #[test]
+    fn collator_follows_the_relay_chain() {
+        let runtime = tokio::runtime::Runtime::new().expect("the tokio runtime starts");
+        let (config, para_id) = dev_config(runtime.handle().clone());
+        let relay_chain = MockRelayChain::new(para_id);
+
+        runtime.block_on(async {
+            let (_task_manager, client) = author_blocks(config, para_id, &relay_chain, 3).await;
+
+            // Finalizing a relay block finalizes the parachain block it includes.
+            let included = client.chain_info().best_hash;
+            let included_header = client
+                .header(included)
+                .expect("the best header is readable")
+                .expect("the best block exists");
+            let relay_block = relay_chain.import_block(HeadData(included_header.encode()));
+            relay_chain.finalize(relay_block);
+
+            tokio::time::timeout(TIMEOUT, async {
+                while client.chain_info().finalized_number < *included_header.number() {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+            })
+            .await
+            .expect("the included block is finalized");
+            assert_eq!(client.chain_info().finalized_hash, included);
+        });
+    }
+
+    #[test]
+    fn authored_blocks_pass_the_block_announce_check() {
+        let runtime = tokio::runtime::Runtime::new().expect("the tokio runtime starts");
+        let (config, para_id) = dev_config(runtime.handle().clone());
+        let relay_chain = MockRelayChain::new(para_id);
+
+        runtime.block_on(async {
+            // Blocks after the first one belong to the epoch that starts at the first one.
+            let (_task_manager, client) = author_blocks(config, para_id, &relay_chain, 2).await;
+            let header = client
+                .header(client.chain_info().best_hash)
+                .expect("the best header is readable")
+                .expect("the best block exists");
+            let epoch = client
+                .runtime_api()
+                .current_epoch(*header.parent_hash())
+                .expect("the epoch is known at the parent");
+            let slot = find_pre_digest::<Block>(&header)
+                .expect("authored blocks carry a pre-digest")
+                .slot();
+
+            assert_eq!(check_header(&header, &epoch, slot), Ok(()));
+
+            let mut tampered = header.clone();
+            tampered.set_state_root(Default::default());
+            assert_eq!(
+                check_header(&tampered, &epoch, slot),
+                Err(AnnounceError::BadSignature)
+            );
+        });
+    }
+
+    #[test]
+    fn authored_blocks_are_submitted_as_collations() {
+        let runtime = tokio::runtime::Runtime::new().expect("the tokio runtime starts");
+        let (config, para_id) = dev_config(runtime.handle().clone());
+        let relay_chain = MockRelayChain::new(para_id);
+        let mut overseer_messages = relay_chain.overseer_messages();
+
+        runtime.block_on(async {
+            let (_task_manager, client) = author_blocks(config, para_id, &relay_chain, 2).await;
+            let authored = client
+                .header(client.chain_info().best_hash)
+                .expect("the best header is readable")
+                .expect("the best block exists");
+            let parent = client
+                .header(*authored.parent_hash())
+                .expect("the parent header is readable")
+                .expect("the parent block exists");
+
+            // The collator registers with the collation generation subsystem before it submits.
+            match overseer_messages.next().await {
+                Some(AllMessages::CollationGeneration(CollationGenerationMessage::Initialize(
+                    config,
+                ))) => assert_eq!(config.para_id, para_id),
+                _ => panic!("the collator registers before it submits collations"),
+            }
+
+            let submitted = tokio::time::timeout(TIMEOUT, async {
+                while let Some(message) = overseer_messages.next().await {
+                    if let AllMessages::CollationGeneration(
+                        CollationGenerationMessage::SubmitCollation(params),
+                    ) = message
+                    {
+                        if params.collation.head_data == HeadData(authored.encode()) {
+                            return params;
+                        }
+                    }
+                }
+                panic!("the overseer messages ended");
+            })
+            .await
+            .expect("the authored block is submitted as a collation");
+
+            assert_eq!(submitted.parent_head, HeadData(parent.encode()));
+        });
+    }
+}
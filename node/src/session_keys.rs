@@ -0,0 +1,168 @@
+//! Implementation of the `session-keys` sub-commands.
+
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use parachain_template_runtime::{opaque::Block, AccountId, SessionKeys};
+use sc_cli::{CliConfiguration, DatabaseParams, KeystoreParams, Result, SharedParams};
+use sc_client_api::{StorageKey, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+    crypto::{ByteArray, Ss58Codec},
+    ed25519,
+    hashing::{twox_128, twox_64},
+    sr25519, Pair,
+};
+use sp_keystore::KeystorePtr;
+
+use crate::cli::{GenerateSessionKeysCmd, InsertSessionKeysCmd, VerifySessionKeysCmd};
+
+/// Print the SCALE-encoded `keys`, ready to be submitted with `session.setKeys`.
+fn print_session_keys(keys: &SessionKeys) {
+    println!(
+        "BABE (sr25519):     {}",
+        sp_core::bytes::to_hex(keys.babe.as_slice(), false)
+    );
+    println!(
+        "GRANDPA (ed25519):  {}",
+        sp_core::bytes::to_hex(keys.grandpa.as_slice(), false)
+    );
+    println!(
+        "Session keys:       {}",
+        sp_core::bytes::to_hex(&keys.encode(), false)
+    );
+}
+
+impl GenerateSessionKeysCmd {
+    /// Run the command.
+    pub fn run(&self, keystore: KeystorePtr) -> Result<()> {
+        let babe = keystore
+            .sr25519_generate_new(sp_consensus_babe::KEY_TYPE, None)
+            .map_err(|e| sc_cli::Error::Application(Box::new(e)))?;
+        let grandpa = keystore
+            .ed25519_generate_new(sp_consensus_grandpa::KEY_TYPE, None)
+            .map_err(|e| sc_cli::Error::Application(Box::new(e)))?;
+
+        print_session_keys(&SessionKeys {
+            babe: babe.into(),
+            grandpa: grandpa.into(),
+        });
+
+        Ok(())
+    }
+}
+
+impl InsertSessionKeysCmd {
+    /// Run the command.
+    pub fn run(&self, keystore: KeystorePtr) -> Result<()> {
+        let suri = sc_cli::utils::read_uri(self.suri.as_ref())?;
+
+        let babe = sr25519::Pair::from_string(&suri, None)
+            .map_err(|_| sc_cli::Error::InvalidUri(sp_core::crypto::PublicError::InvalidFormat))?
+            .public();
+        let grandpa = ed25519::Pair::from_string(&suri, None)
+            .map_err(|_| sc_cli::Error::InvalidUri(sp_core::crypto::PublicError::InvalidFormat))?
+            .public();
+
+        keystore
+            .insert(sp_consensus_babe::KEY_TYPE, &suri, babe.as_ref())
+            .map_err(|_| sc_cli::Error::KeystoreOperation)?;
+        keystore
+            .insert(sp_consensus_grandpa::KEY_TYPE, &suri, grandpa.as_ref())
+            .map_err(|_| sc_cli::Error::KeystoreOperation)?;
+
+        print_session_keys(&SessionKeys {
+            babe: babe.into(),
+            grandpa: grandpa.into(),
+        });
+
+        Ok(())
+    }
+}
+
+impl VerifySessionKeysCmd {
+    /// Run the command.
+    pub fn run<C, BE>(&self, client: Arc<C>, keystore: KeystorePtr) -> Result<()>
+    where
+        C: HeaderBackend<Block> + StorageProvider<Block, BE>,
+        BE: sc_client_api::Backend<Block>,
+    {
+        let validator = AccountId::from_ss58check(&self.validator)
+            .map_err(|e| format!("Invalid validator address: {:?}", e))?;
+
+        let at = match &self.at {
+            Some(at) => {
+                let id = at.parse::<Block>()?;
+                client
+                    .block_hash_from_id(&id)?
+                    .ok_or_else(|| format!("Block {} not found", at))?
+            }
+            None => client.info().best_hash,
+        };
+
+        // `Session::NextKeys` is a `Twox64Concat` map from validator id to session keys.
+        let encoded_validator = validator.encode();
+        let key = [
+            &twox_128(b"Session")[..],
+            &twox_128(b"NextKeys")[..],
+            &twox_64(&encoded_validator)[..],
+            &encoded_validator[..],
+        ]
+        .concat();
+
+        let keys = client
+            .storage(at, &StorageKey(key))?
+            .map(|data| SessionKeys::decode(&mut &data.0[..]))
+            .transpose()
+            .map_err(|e| format!("Failed to decode the registered session keys: {}", e))?
+            .ok_or_else(|| format!("{} has no session keys registered at {:?}", validator, at))?;
+
+        print_session_keys(&keys);
+
+        let has_babe = keystore.has_keys(&[(keys.babe.to_raw_vec(), sp_consensus_babe::KEY_TYPE)]);
+        let has_grandpa =
+            keystore.has_keys(&[(keys.grandpa.to_raw_vec(), sp_consensus_grandpa::KEY_TYPE)]);
+        println!("BABE key in keystore:     {}", has_babe);
+        println!("GRANDPA key in keystore:  {}", has_grandpa);
+
+        if has_babe && has_grandpa {
+            Ok(())
+        } else {
+            Err("The keystore does not hold all registered session keys".into())
+        }
+    }
+}
+
+impl CliConfiguration for GenerateSessionKeysCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn keystore_params(&self) -> Option<&KeystoreParams> {
+        Some(&self.keystore_params)
+    }
+}
+
+impl CliConfiguration for InsertSessionKeysCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn keystore_params(&self) -> Option<&KeystoreParams> {
+        Some(&self.keystore_params)
+    }
+}
+
+impl CliConfiguration for VerifySessionKeysCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn keystore_params(&self) -> Option<&KeystoreParams> {
+        Some(&self.keystore_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
@@ -0,0 +1,258 @@
+//! Implementation of the `try-runtime` sub-commands.
+//!
+//! Unlike the standalone try-runtime CLI, these commands never talk to a remote node. A snapshot
+//! is taken from the local database with `try-runtime create-snapshot` and all checks run against
+//! that file, so they work fully offline.
+
+use std::{path::Path, sync::Arc};
+
+use codec::{Decode, Encode};
+use parachain_template_runtime::opaque::Block;
+use sc_client_api::{BlockBackend, StorageProvider, UsageProvider};
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::storage::{ChildInfo, StateVersion, Storage, StorageChild};
+use sp_runtime::traits::Header as HeaderT;
+
+use crate::cli::CreateSnapshotCmd;
+
+/// State of a block's parent together with the block itself.
+#[derive(Encode, Decode)]
+pub struct Snapshot {
+    /// The block executed by `try-runtime execute-block`.
+    pub block: Block,
+    /// The state version the state was written with.
+    pub state_version: StateVersion,
+    /// All key-values of the main trie at the parent of `block`.
+    pub top: Vec<(Vec<u8>, Vec<u8>)>,
+    /// All key-values of the default child tries, by child storage key.
+    pub children: Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>,
+}
+
+impl Snapshot {
+    /// Read a snapshot written by `try-runtime create-snapshot`.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read(path)
+            .map_err(|e| format!("Failed to read snapshot {}: {}", path.display(), e))?;
+        Self::decode(&mut &data[..])
+            .map_err(|e| format!("Failed to decode snapshot {}: {}", path.display(), e))
+    }
+
+    /// The state of the snapshot.
+    pub fn storage(&self) -> Storage {
+        Storage {
+            top: self.top.iter().cloned().collect(),
+            children_default: self
+                .children
+                .iter()
+                .map(|(storage_key, data)| {
+                    let child = StorageChild {
+                        data: data.iter().cloned().collect(),
+                        child_info: ChildInfo::new_default(storage_key),
+                    };
+                    (storage_key.clone(), child)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl CreateSnapshotCmd {
+    /// Run the command.
+    pub fn run<C, BE>(&self, client: Arc<C>) -> sc_cli::Result<()>
+    where
+        C: HeaderBackend<Block>
+            + BlockBackend<Block>
+            + StorageProvider<Block, BE>
+            + UsageProvider<Block>
+            + ProvideRuntimeApi<Block>,
+        C::Api: Core<Block>,
+        BE: sc_client_api::Backend<Block>,
+    {
+        let at = match &self.at {
+            Some(at) => {
+                let id = at.parse::<Block>()?;
+                client
+                    .block_hash_from_id(&id)?
+                    .ok_or_else(|| format!("Block {} not found", at))?
+            }
+            None => client.info().best_hash,
+        };
+        let block = client
+            .block(at)?
+            .ok_or_else(|| format!("Body of block {:?} not found", at))?
+            .block;
+        let parent = *block.header.parent_hash();
+
+        let state_version = client
+            .runtime_api()
+            .version(parent)
+            .map_err(|e| format!("Failed to get the runtime version: {}", e))?
+            .state_version();
+        let storage = sc_service::chain_ops::export_raw_state(client, parent)?;
+
+        let snapshot = Snapshot {
+            block,
+            state_version,
+            top: storage.top.into_iter().collect(),
+            children: storage
+                .children_default
+                .into_iter()
+                .map(|(storage_key, child)| (storage_key, child.data.into_iter().collect()))
+                .collect(),
+        };
+        std::fs::write(&self.path, snapshot.encode())?;
+
+        log::info!(
+            "Wrote snapshot of block #{} ({:?}) to {}",
+            snapshot.block.header.number(),
+            at,
+            self.path.display(),
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "try-runtime")]
+mod checks {
+    use std::str::FromStr;
+
+    use frame_support::weights::Weight;
+    use frame_try_runtime::{TryStateSelect, UpgradeCheckSelect};
+    use sc_executor::WasmExecutor;
+    use sp_core::traits::CallContext;
+    use sp_runtime::traits::BlakeTwo256;
+    use sp_state_machine::{backend::BackendRuntimeCode, InMemoryBackend, OverlayedChanges};
+
+    use super::*;
+    use crate::cli::{ExecuteBlockCmd, OnRuntimeUpgradeCmd};
+
+    /// Host functions the runtime may call.
+    type HostFunctions = (
+        sp_io::SubstrateHostFunctions,
+        cumulus_client_service::storage_proof_size::HostFunctions,
+        frame_benchmarking::benchmarking::HostFunctions,
+    );
+
+    /// Load the state of `snapshot`, with `:code` replaced by the runtime to test.
+    fn load_state(
+        snapshot: &Snapshot,
+        runtime: Option<&Path>,
+    ) -> Result<InMemoryBackend<BlakeTwo256>, String> {
+        let code = match runtime {
+            Some(path) => std::fs::read(path)
+                .map_err(|e| format!("Failed to read runtime {}: {}", path.display(), e))?,
+            None => parachain_template_runtime::WASM_BINARY
+                .ok_or("WASM binary was not built, please build it!")?
+                .to_vec(),
+        };
+
+        let mut storage = snapshot.storage();
+        storage
+            .top
+            .insert(sp_core::storage::well_known_keys::CODE.to_vec(), code);
+
+        Ok((storage, snapshot.state_version).into())
+    }
+
+    /// Call `method` of the runtime in `backend`.
+    fn call(
+        backend: &InMemoryBackend<BlakeTwo256>,
+        method: &str,
+        data: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let executor = WasmExecutor::<HostFunctions>::builder().build();
+        let runtime_code_backend = BackendRuntimeCode::new(backend);
+        let runtime_code = runtime_code_backend.runtime_code()?;
+
+        sp_state_machine::StateMachine::new(
+            backend,
+            &mut OverlayedChanges::default(),
+            &executor,
+            method,
+            data,
+            &mut Default::default(),
+            &runtime_code,
+            CallContext::Offchain,
+        )
+        .execute()
+        .map_err(|e| format!("Failed to execute {}: {}", method, e))
+    }
+
+    impl OnRuntimeUpgradeCmd {
+        /// Run the command.
+        pub fn run(&self) -> sc_cli::Result<()> {
+            let checks = UpgradeCheckSelect::from_str(&self.checks)?;
+            let snapshot = Snapshot::from_file(&self.snapshot)?;
+            let backend = load_state(&snapshot, self.runtime.as_deref())?;
+
+            let result = call(&backend, "TryRuntime_on_runtime_upgrade", &checks.encode())?;
+            let (weight, max_weight) = <(Weight, Weight)>::decode(&mut &result[..])
+                .map_err(|e| format!("Failed to decode the upgrade weight: {}", e))?;
+
+            log::info!(
+                "Runtime upgrade on top of block #{} succeeded, consuming {:?} of {:?} ({:.1}%) of \
+                 the block weight",
+                snapshot.block.header.number().saturating_sub(1),
+                weight,
+                max_weight,
+                weight.ref_time() as f64 * 100.0 / max_weight.ref_time() as f64,
+            );
+
+            Ok(())
+        }
+    }
+
+    impl ExecuteBlockCmd {
+        /// Run the command.
+        pub fn run(&self) -> sc_cli::Result<()> {
+            let try_state = TryStateSelect::from_str(&self.try_state)?;
+            let snapshot = Snapshot::from_file(&self.snapshot)?;
+            let backend = load_state(&snapshot, self.runtime.as_deref())?;
+
+            let signature_check = true;
+            let payload = (
+                &snapshot.block,
+                self.state_root_check,
+                signature_check,
+                try_state,
+            )
+                .encode();
+            let result = call(&backend, "TryRuntime_execute_block", &payload)?;
+            let weight = Weight::decode(&mut &result[..])
+                .map_err(|e| format!("Failed to decode the block weight: {}", e))?;
+
+            log::info!(
+                "Executed block #{} ({:?}), consuming {:?}",
+                snapshot.block.header.number(),
+                snapshot.block.header.hash(),
+                weight,
+            );
+
+            Ok(())
+        }
+    }
+}
+
+impl sc_cli::CliConfiguration for CreateSnapshotCmd {
+    fn shared_params(&self) -> &sc_cli::SharedParams {
+        &self.shared_params
+    }
+
+    fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
+
+impl sc_cli::CliConfiguration for crate::cli::OnRuntimeUpgradeCmd {
+    fn shared_params(&self) -> &sc_cli::SharedParams {
+        &self.shared_params
+    }
+}
+
+impl sc_cli::CliConfiguration for crate::cli::ExecuteBlockCmd {
+    fn shared_params(&self) -> &sc_cli::SharedParams {
+        &self.shared_params
+    }
+}
@@ -0,0 +1,244 @@
+//! Genesis config presets of the runtime.
+//!
+//! Chain specs are built by patching the default genesis config with one of the presets below,
+//! so the genesis of our networks is defined together with the runtime it is meant for. The node
+//! reads them through [`GenesisPresetsApi`].
+//!
+//! The presets use the well-known development keys, which the runtime can't derive from seeds, so
+//! they are spelled out. Networks run by independent operators take their keys and endowments from
+//! a manifest instead, see the `generate-spec` command of the node.
+
+use crate::{
+    AccountId, BabeId, Balance, BlockNumber, GrandpaId, SessionKeys, DAYS, EXISTENTIAL_DEPOSIT,
+};
+use cumulus_primitives_core::ParaId;
+use hex_literal::hex;
+use sp_core::{ed25519, sr25519};
+use sp_std::prelude::*;
+
+/// Preset for a single developer network.
+pub const DEVELOPMENT: &str = "development";
+/// Preset for a local multi-node test network, the same genesis as [`DEVELOPMENT`].
+pub const LOCAL_TESTNET: &str = "local_testnet";
+/// Preset for the public staging network.
+pub const STAGING: &str = "staging";
+
+/// The para id all presets are configured for.
+pub const PARACHAIN_ID: u32 = 1000;
+
+/// The name of a preset, as UTF-8 bytes.
+pub type PresetId = Vec<u8>;
+
+sp_api::decl_runtime_apis! {
+    /// The genesis config presets of the runtime.
+    pub trait GenesisPresetsApi {
+        /// The JSON patch of the preset `id`, if the runtime has it.
+        fn get_preset(id: PresetId) -> Option<Vec<u8>>;
+        /// The names of all presets.
+        fn preset_names() -> Vec<PresetId>;
+    }
+}
+
+/// The default XCM version to set in genesis config.
+const SAFE_XCM_VERSION: u32 = xcm::prelude::XCM_VERSION;
+
+/// The balance every endowed account starts with.
+pub const ENDOWMENT: Balance = 1 << 60;
+
+/// A vesting schedule set up in genesis: the account, the block vesting begins at, the number of
+/// blocks it takes and the part of the balance that is liquid from the start.
+pub type GenesisVesting = (AccountId, BlockNumber, BlockNumber, Balance);
+
+/// sr25519 public keys of `//Alice` ... `//Ferdie`.
+const DEV_SR25519: [[u8; 32]; 6] = [
+    hex!("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"),
+    hex!("8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48"),
+    hex!("90b5ab205c6974c9ea841be688864633dc9ca8a357843eeacf2314649965fe22"),
+    hex!("306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20"),
+    hex!("e659a7a1628cdd93febc04a4e0646ea20e9f5f0ce097d9a05290d4a9e054df4e"),
+    hex!("1cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07c"),
+];
+
+/// sr25519 public keys of `//Alice//stash` ... `//Ferdie//stash`.
+const DEV_STASH_SR25519: [[u8; 32]; 6] = [
+    hex!("be5ddb1579b72e84524fc29e78609e3caf42e85aa118ebfe0b0ad404b5bdd25f"),
+    hex!("fe65717dad0447d715f660a0a58411de509b42e6efb8375f562f58a554d5860e"),
+    hex!("1e07379407fecc4b89eb7dbd287c2c781cfb1907a96947a3eb18e4f8e7198625"),
+    hex!("e860f1b1c7227f7c22602f53f15af80747814dffd839719731ee3bba6edc126c"),
+    hex!("8ac59e11963af19174d0b94d5d78041c233f55d2e19324665bafdfb62925af2d"),
+    hex!("101191192fc877c24d725b337120fa3edc63d227bbc92705db1e2cb65f56981a"),
+];
+
+/// ed25519 public keys of `//Alice` and `//Bob`.
+const DEV_ED25519: [[u8; 32]; 2] = [
+    hex!("88dc3417d5058ec4b4503e0c12ea1a0a89be200fe98922423d4334014fa6b0ee"),
+    hex!("d17c2d7823ebf260fd138f2d7e27d114c0145d968b5ff5006125f2414fadae69"),
+];
+
+fn dev_account(index: usize) -> AccountId {
+    DEV_SR25519[index].into()
+}
+
+/// The accounts, BABE and GRANDPA keys of `//Alice` and `//Bob`.
+fn dev_invulnerables() -> Vec<(AccountId, BabeId, GrandpaId)> {
+    (0..2)
+        .map(|i| {
+            (
+                dev_account(i),
+                sr25519::Public::from_raw(DEV_SR25519[i]).into(),
+                ed25519::Public::from_raw(DEV_ED25519[i]).into(),
+            )
+        })
+        .collect()
+}
+
+/// All well-known development accounts and their stashes.
+fn dev_endowed_accounts() -> Vec<AccountId> {
+    DEV_SR25519
+        .iter()
+        .chain(DEV_STASH_SR25519.iter())
+        .map(|key| AccountId::from(*key))
+        .collect()
+}
+
+/// The development stashes hold the allocation of early contributors, a tenth of it is liquid and
+/// the rest vests over a year.
+fn dev_vesting() -> Vec<GenesisVesting> {
+    DEV_STASH_SR25519
+        .iter()
+        .map(|key| (AccountId::from(*key), 0, 365 * DAYS, ENDOWMENT / 10))
+        .collect()
+}
+
+/// Generate the session keys from individual elements.
+pub fn session_keys(babe: BabeId, grandpa: GrandpaId) -> SessionKeys {
+    SessionKeys { babe, grandpa }
+}
+
+/// Build the genesis config patch shared by all presets.
+pub fn testnet_genesis(
+    invulnerables: Vec<(AccountId, BabeId, GrandpaId)>,
+    endowed_accounts: Vec<AccountId>,
+    vesting: Vec<GenesisVesting>,
+    id: ParaId,
+) -> serde_json::Value {
+    serde_json::json!({
+        "balances": {
+            "balances": endowed_accounts.iter().cloned().map(|k| (k, ENDOWMENT)).collect::<Vec<_>>(),
+        },
+        "vesting": {
+            "vesting": vesting,
+        },
+        "parachainInfo": {
+            "parachainId": id,
+        },
+        "relayChainSlot": {
+            "slotDurationMillis": crate::relay_chain_slot::DEFAULT_SLOT_DURATION_MILLIS,
+        },
+        "collatorSelection": {
+            "invulnerables": invulnerables.iter().cloned().map(|(acc, _babe, _grandpa)| acc).collect::<Vec<_>>(),
+            "candidacyBond": EXISTENTIAL_DEPOSIT * 16,
+        },
+        "session": {
+            "keys": invulnerables
+                .into_iter()
+                .map(|x| {
+                    (
+                        x.0.clone(),                 // account id
+                        x.0.clone(),                 // validator id
+                        session_keys(x.1.clone(), x.2.clone()), // session keys
+                    )
+                })
+            .collect::<Vec<_>>(),
+        },
+        "polkadotXcm": {
+            "safeXcmVersion": Some(SAFE_XCM_VERSION),
+        },
+    })
+}
+
+/// The development authorities and accounts, shared by the development and local testnet presets.
+fn local_testnet_genesis() -> serde_json::Value {
+    testnet_genesis(
+        dev_invulnerables(),
+        dev_endowed_accounts(),
+        dev_vesting(),
+        PARACHAIN_ID.into(),
+    )
+}
+
+/// The staging network runs the development authorities until its own validators have rotated
+/// in their keys, but only endows Alice, who can pay the deposits of the first referenda.
+fn staging_genesis() -> serde_json::Value {
+    testnet_genesis(
+        dev_invulnerables(),
+        vec![dev_account(0)],
+        vec![],
+        PARACHAIN_ID.into(),
+    )
+}
+
+/// Provides the JSON patch of the preset with the given `id`.
+pub fn get_preset(id: &str) -> Option<Vec<u8>> {
+    let patch = match id {
+        DEVELOPMENT | LOCAL_TESTNET => local_testnet_genesis(),
+        STAGING => staging_genesis(),
+        _ => return None,
+    };
+    Some(
+        serde_json::to_string(&patch)
+            .expect("serialization to json is expected to work. qed.")
+            .into_bytes(),
+    )
+}
+
+/// List of the supported presets.
+pub fn preset_names() -> Vec<&'static str> {
+    vec![DEVELOPMENT, LOCAL_TESTNET, STAGING]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+
+    #[test]
+    fn development_keys_match_keyring() {
+        let accounts = [
+            Sr25519Keyring::Alice,
+            Sr25519Keyring::Bob,
+            Sr25519Keyring::Charlie,
+            Sr25519Keyring::Dave,
+            Sr25519Keyring::Eve,
+            Sr25519Keyring::Ferdie,
+        ];
+        for (i, account) in accounts.iter().enumerate() {
+            assert_eq!(DEV_SR25519[i], account.public().0);
+        }
+
+        let stashes = [
+            "//Alice//stash",
+            "//Bob//stash",
+            "//Charlie//stash",
+            "//Dave//stash",
+            "//Eve//stash",
+            "//Ferdie//stash",
+        ];
+        for (i, stash) in stashes.iter().enumerate() {
+            let pair = <sr25519::Pair as sp_core::Pair>::from_string(stash, None).unwrap();
+            let public = sp_core::Pair::public(&pair);
+            assert_eq!(DEV_STASH_SR25519[i], public.0);
+        }
+
+        assert_eq!(DEV_ED25519[0], Ed25519Keyring::Alice.public().0);
+        assert_eq!(DEV_ED25519[1], Ed25519Keyring::Bob.public().0);
+    }
+
+    #[test]
+    fn all_presets_are_available() {
+        for name in preset_names() {
+            assert!(get_preset(name).is_some(), "preset {} is missing", name);
+        }
+        assert!(get_preset("unknown").is_none());
+    }
+}
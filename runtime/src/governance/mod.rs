@@ -0,0 +1,130 @@
+//! On-chain governance.
+//!
+//! Privileged calls are dispatched by referenda, voted on with conviction-weighted balances.
+//! Besides `Root`, each track dispatches with one of the [`origins`], so admin tasks such as
+//! collator selection or the XCM configuration can be decided without the stake and delays of a
+//! root referendum. There is no sudo key.
+
+use crate::{
+    xcm_config::RelayLocation, AccountId, Balance, Balances, BlockNumber, OriginCaller, Preimage,
+    Referenda, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, Scheduler, Treasury, DAYS, HOURS,
+    MINUTES, UNIT,
+};
+use core::cmp::Ordering;
+use frame_support::{
+    parameter_types,
+    traits::{ConstU32, EitherOf, EitherOfDiverse, PrivilegeCmp},
+};
+use frame_system::{EnsureRoot, EnsureRootWithSuccess, EnsureSigned, EnsureWithSuccess};
+use pallet_xcm::{EnsureXcm, IsVoiceOfBody};
+use xcm::latest::BodyId;
+
+pub mod origins;
+pub use origins::{
+    pallet_custom_origins, GeneralAdmin, ReferendumCanceller, ReferendumKiller, StakingAdmin,
+    Treasurer, WhitelistedCaller,
+};
+mod tracks;
+pub use tracks::TracksInfo;
+
+/// Root, or a referendum on the general admin track.
+pub type GeneralAdminOrRoot = EitherOf<EnsureRoot<AccountId>, GeneralAdmin>;
+
+/// Root, or a referendum on the staking admin track.
+pub type StakingAdminOrRoot = EitherOf<EnsureRoot<AccountId>, StakingAdmin>;
+
+/// Root, or a referendum on one of the admin tracks. Calls they schedule are dispatched with the
+/// same origin once they are due.
+pub type ScheduleOrigin =
+    EitherOf<EnsureRoot<AccountId>, EitherOf<StakingAdmin, EitherOf<Treasurer, GeneralAdmin>>>;
+
+/// Compares the privilege of origins in the scheduler, which may only cancel or reschedule calls
+/// of origins that are at most as privileged.
+///
+/// Root outranks every other origin. The admin origins only compare equal to themselves, so a
+/// track can cancel what it scheduled but not what another track did.
+pub struct OriginPrivilegeCmp;
+impl PrivilegeCmp<OriginCaller> for OriginPrivilegeCmp {
+    fn cmp_privilege(left: &OriginCaller, right: &OriginCaller) -> Option<Ordering> {
+        if left == right {
+            return Some(Ordering::Equal);
+        }
+        match (left, right) {
+            (OriginCaller::system(frame_system::RawOrigin::Root), _) => Some(Ordering::Greater),
+            _ => None,
+        }
+    }
+}
+
+parameter_types! {
+    pub const MaxBalance: Balance = Balance::MAX;
+    /// The most a referendum on the treasurer track can spend at once.
+    pub const MaxTreasurerSpend: Balance = 10_000 * UNIT;
+}
+
+/// Origins allowed to spend from the treasury, with the most they may spend.
+pub type TreasurySpender = EitherOf<
+    EnsureRootWithSuccess<AccountId, MaxBalance>,
+    EnsureWithSuccess<Treasurer, AccountId, MaxTreasurerSpend>,
+>;
+
+parameter_types! {
+    pub const VoteLockingPeriod: BlockNumber = 7 * DAYS;
+}
+
+impl pallet_conviction_voting::Config for Runtime {
+    type WeightInfo = pallet_conviction_voting::weights::SubstrateWeight<Runtime>;
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type VoteLockingPeriod = VoteLockingPeriod;
+    type MaxVotes = ConstU32<512>;
+    type MaxTurnout =
+        frame_support::traits::tokens::currency::ActiveIssuanceOf<Balances, Self::AccountId>;
+    type Polls = Referenda;
+}
+
+parameter_types! {
+    pub const AlarmInterval: BlockNumber = 1;
+    pub const SubmissionDeposit: Balance = UNIT;
+    pub const UndecidingTimeout: BlockNumber = 14 * DAYS;
+}
+
+impl pallet_custom_origins::Config for Runtime {}
+
+parameter_types! {
+    // The technical body of the relay chain, until the chain has a technical collective of its
+    // own.
+    pub const TechnicalBodyId: BodyId = BodyId::Technical;
+}
+
+impl pallet_whitelist::Config for Runtime {
+    type WeightInfo = pallet_whitelist::weights::SubstrateWeight<Runtime>;
+    type RuntimeCall = RuntimeCall;
+    type RuntimeEvent = RuntimeEvent;
+    type WhitelistOrigin = EitherOfDiverse<
+        EnsureRoot<AccountId>,
+        EnsureXcm<IsVoiceOfBody<RelayLocation, TechnicalBodyId>>,
+    >;
+    type DispatchWhitelistedOrigin = EitherOf<EnsureRoot<AccountId>, WhitelistedCaller>;
+    type Preimages = Preimage;
+}
+
+impl pallet_referenda::Config for Runtime {
+    type WeightInfo = pallet_referenda::weights::SubstrateWeight<Runtime>;
+    type RuntimeCall = RuntimeCall;
+    type RuntimeEvent = RuntimeEvent;
+    type Scheduler = Scheduler;
+    type Currency = Balances;
+    type SubmitOrigin = EnsureSigned<AccountId>;
+    type CancelOrigin = EitherOf<EnsureRoot<AccountId>, ReferendumCanceller>;
+    type KillOrigin = EitherOf<EnsureRoot<AccountId>, ReferendumKiller>;
+    type Slash = Treasury;
+    type Votes = pallet_conviction_voting::VotesOf<Runtime>;
+    type Tally = pallet_conviction_voting::TallyOf<Runtime>;
+    type SubmissionDeposit = SubmissionDeposit;
+    type MaxQueued = ConstU32<100>;
+    type UndecidingTimeout = UndecidingTimeout;
+    type AlarmInterval = AlarmInterval;
+    type Tracks = TracksInfo;
+    type Preimages = Preimage;
+}
@@ -0,0 +1,74 @@
+//! Custom origins for governance interventions.
+
+pub use pallet_custom_origins::*;
+
+// The pallet macro clones `PhantomData` in the code it generates.
+#[allow(clippy::clone_on_copy)]
+#[frame_support::pallet]
+pub mod pallet_custom_origins {
+    use frame_support::pallet_prelude::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {}
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// The origins of the referendum tracks, other than `Root`.
+    #[derive(PartialEq, Eq, Clone, MaxEncodedLen, Encode, Decode, TypeInfo, RuntimeDebug)]
+    #[pallet::origin]
+    pub enum Origin {
+        /// Origin for administering collator selection and, once enabled, staking.
+        StakingAdmin,
+        /// Origin for spending funds of the treasury.
+        Treasurer,
+        /// Origin for administering the XCM configuration and message queues.
+        GeneralAdmin,
+        /// Origin able to cancel referenda.
+        ReferendumCanceller,
+        /// Origin able to kill referenda.
+        ReferendumKiller,
+        /// Origin able to dispatch a whitelisted call.
+        WhitelistedCaller,
+    }
+
+    macro_rules! decl_unit_ensures {
+        ( $name:ident: $success_type:ty = $success:expr ) => {
+            /// Ensures the origin is the
+            #[doc = concat!("[`Origin::", stringify!($name), "`]")]
+            /// origin.
+            pub struct $name;
+            impl<O: Into<Result<Origin, O>> + From<Origin>> EnsureOrigin<O> for $name {
+                type Success = $success_type;
+                fn try_origin(o: O) -> Result<Self::Success, O> {
+                    o.into().and_then(|o| match o {
+                        Origin::$name => Ok($success),
+                        r => Err(O::from(r)),
+                    })
+                }
+                #[cfg(feature = "runtime-benchmarks")]
+                fn try_successful_origin() -> Result<O, ()> {
+                    Ok(O::from(Origin::$name))
+                }
+            }
+        };
+        ( $name:ident ) => { decl_unit_ensures! { $name : () = () } };
+        ( $name:ident: $success_type:ty = $success:expr, $( $rest:tt )* ) => {
+            decl_unit_ensures! { $name: $success_type = $success }
+            decl_unit_ensures! { $( $rest )* }
+        };
+        ( $name:ident, $( $rest:tt )* ) => {
+            decl_unit_ensures! { $name }
+            decl_unit_ensures! { $( $rest )* }
+        };
+        () => {}
+    }
+    decl_unit_ensures!(
+        StakingAdmin,
+        Treasurer,
+        GeneralAdmin,
+        ReferendumCanceller,
+        ReferendumKiller,
+        WhitelistedCaller,
+    );
+}
@@ -6,9 +6,15 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+pub mod genesis_config_presets;
+pub mod governance;
+pub mod migrations;
+pub mod relay_chain_slot;
+pub mod staking_api;
 mod weights;
 pub mod xcm_config;
 
+use codec::{Decode, Encode, MaxEncodedLen};
 use cumulus_pallet_parachain_system::RelayNumberMonotonicallyIncreases;
 use polkadot_runtime_common::xcm_sender::NoPriceForMessageDelivery;
 use smallvec::smallvec;
@@ -16,9 +22,12 @@ use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
-    traits::{BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, Verify},
+    traits::{
+        BlakeTwo256, Block as BlockT, ConvertInto, IdentifyAccount, IdentityLookup, NumberFor,
+        Verify,
+    },
     transaction_validity::{TransactionSource, TransactionValidity},
-    ApplyExtrinsicResult, MultiSignature,
+    ApplyExtrinsicResult, MultiSignature, RuntimeDebug,
 };
 
 use sp_std::prelude::*;
@@ -33,8 +42,12 @@ use frame_support::{
     genesis_builder_helper::{build_config, create_default_config},
     parameter_types,
     traits::{
-        ConstU32, ConstU64, ConstU8, EitherOfDiverse, ExecuteBlock, KeyOwnerProofSystem,
-        TransformOrigin,
+        fungible::HoldConsideration,
+        fungibles::{self, Balanced},
+        tokens::{PayFromAccount, UnityAssetBalanceConversion},
+        AsEnsureOriginWithArg, ConstU32, ConstU64, ConstU8, Currency, EitherOf, EitherOfDiverse,
+        ExecuteBlock, Imbalance, InstanceFilter, KeyOwnerProofSystem, LinearStoragePrice,
+        OnUnbalanced, TransformOrigin, WithdrawReasons,
     },
     weights::{
         constants::WEIGHT_REF_TIME_PER_SECOND, ConstantMultiplier, Weight, WeightToFeeCoefficient,
@@ -44,22 +57,30 @@ use frame_support::{
 };
 use frame_system::{
     limits::{BlockLength, BlockWeights},
-    EnsureRoot,
+    EnsureNever, EnsureRoot, EnsureSigned,
 };
+use governance::{
+    pallet_custom_origins, GeneralAdminOrRoot, StakingAdminOrRoot, Treasurer, TreasurySpender,
+};
+use pallet_asset_tx_payment::HandleCredit;
+use pallet_assets::BalanceToAssetBalance;
 use pallet_xcm::{EnsureXcm, IsVoiceOfBody};
 use parachains_common::message_queue::{NarrowOriginToSibling, ParaIdToSibling};
+use relay_chain_slot::pallet_relay_chain_slot;
 pub use sp_consensus_babe::AuthorityId as BabeId;
 pub use sp_consensus_grandpa::AuthorityId as GrandpaId;
-pub use sp_runtime::{MultiAddress, Perbill, Permill};
+pub use sp_runtime::{MultiAddress, Perbill, Percent, Permill};
 use xcm_config::{RelayLocation, XcmOriginToTransactDispatchOrigin};
 
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
 
 // Polkadot imports
-use polkadot_runtime_common::{BlockHashCount, SlowAdjustingFeeUpdate};
+use polkadot_runtime_common::{impls::ToAuthor, BlockHashCount, SlowAdjustingFeeUpdate};
+
+use weights::{BlockExecutionWeight, DbWeight, ExtrinsicBaseWeight};
 
-use weights::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight};
+pub use weights::{DbWeightApi, DB_WEIGHT_BACKEND};
 
 // XCM Imports
 use xcm::latest::prelude::BodyId;
@@ -110,7 +131,7 @@ pub type SignedExtra = (
     frame_system::CheckEra<Runtime>,
     frame_system::CheckNonce<Runtime>,
     frame_system::CheckWeight<Runtime>,
-    pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+    pallet_asset_tx_payment::ChargeAssetTxPayment<Runtime>,
     cumulus_primitives_storage_weight_reclaim::StorageWeightReclaim<Runtime>,
 );
 
@@ -118,6 +139,9 @@ pub type SignedExtra = (
 pub type UncheckedExtrinsic =
     generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 
+/// Migrations to apply on runtime upgrade.
+pub type Migrations = migrations::Unreleased;
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
     Runtime,
@@ -125,6 +149,7 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
+    Migrations,
 >;
 
 /// Handles converting a weight scalar to a fee value, based on the scale and granularity of the
@@ -188,10 +213,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("parachain-template-runtime"),
     impl_name: create_runtime_str!("parachain-template-runtime"),
     authoring_version: 1,
-    spec_version: 1,
+    spec_version: 2,
     impl_version: 0,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 3,
     state_version: 1,
 };
 
@@ -271,8 +296,6 @@ const UNINCLUDED_SEGMENT_CAPACITY: u32 = 3;
 /// How many parachain blocks are processed by the relay chain per parent. Limits the
 /// number of blocks authored per slot.
 const BLOCK_PROCESSING_VELOCITY: u32 = 1;
-/// Relay chain slot duration, in milliseconds.
-const RELAY_CHAIN_SLOT_DURATION_MILLIS: u32 = 6000;
 
 /// The version information used to identify this runtime when compiled natively.
 #[cfg(feature = "std")]
@@ -351,8 +374,9 @@ impl frame_system::Config for Runtime {
     type Version = Version;
     /// The data to be stored in an account.
     type AccountData = pallet_balances::AccountData<Balance>;
-    /// The weight of database operations that the runtime can invoke.
-    type DbWeight = RocksDbWeight;
+    /// The weight of database operations that the runtime can invoke, for the backend selected
+    /// with the `paritydb-weights` feature.
+    type DbWeight = DbWeight;
     /// Block & extrinsics weights: base values and limits.
     type BlockWeights = RuntimeBlockWeights;
     /// The maximum length of a block (in bytes).
@@ -372,7 +396,7 @@ impl pallet_timestamp::Config for Runtime {
     type MinimumPeriod = ConstU64<0>;
     #[cfg(not(feature = "experimental"))]
     type MinimumPeriod = ConstU64<{ SLOT_DURATION / 2 }>;
-    type WeightInfo = ();
+    type WeightInfo = weights::pallet_timestamp::WeightInfo<Runtime>;
 }
 
 impl pallet_authorship::Config for Runtime {
@@ -393,10 +417,11 @@ parameter_types! {
 impl pallet_babe::Config for Runtime {
     type EpochDuration = EpochDuration;
     type ExpectedBlockTime = ExpectedBlockTime;
-    type EpochChangeTrigger = pallet_babe::SameAuthoritiesForever;
+    // Epochs are changed by the session pallet, one session per epoch.
+    type EpochChangeTrigger = pallet_babe::ExternalTrigger;
     // Note: DisabledValidators trait is implemented for the pallet_session
     type DisabledValidators = Session;
-    type WeightInfo = ();
+    type WeightInfo = weights::pallet_babe::WeightInfo<Runtime>;
     type MaxAuthorities = MaxAuthorities;
     type MaxNominators = MaxNominators;
     type KeyOwnerProof = <Historical as KeyOwnerProofSystem<(KeyTypeId, BabeId)>>::Proof;
@@ -406,7 +431,7 @@ impl pallet_babe::Config for Runtime {
 
 impl pallet_grandpa::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
-    type WeightInfo = ();
+    type WeightInfo = weights::pallet_grandpa::WeightInfo<Runtime>;
     type MaxAuthorities = ConstU32<32>;
     type MaxNominators = MaxNominators;
     type MaxSetIdSessionEntries = ConstU64<0>;
@@ -415,9 +440,18 @@ impl pallet_grandpa::Config for Runtime {
         pallet_grandpa::EquivocationReportSystem<Self, Offences, Historical, ReportLongevity>;
 }
 
+/// Identifies a validator by its account alone. Collators have no stake exposure to record, but
+/// key ownership proofs leave out validators without a full identification.
+pub struct FullIdentificationOf;
+impl sp_runtime::traits::Convert<AccountId, Option<()>> for FullIdentificationOf {
+    fn convert(_: AccountId) -> Option<()> {
+        Some(())
+    }
+}
+
 impl pallet_session::historical::pallet::Config for Runtime {
     type FullIdentification = ();
-    type FullIdentificationOf = ();
+    type FullIdentificationOf = FullIdentificationOf;
 }
 
 impl pallet_balances::Config for Runtime {
@@ -443,19 +477,448 @@ parameter_types! {
     pub const TransactionByteFee: Balance = 10 * MICROUNIT;
 }
 
+parameter_types! {
+    /// Share of transaction fees, tips and XCM execution fees paid into the treasury. The rest
+    /// goes to the block author.
+    pub storage TreasuryFeeShare: Percent = Percent::from_percent(80);
+}
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Splits fees between the treasury and the block author, by [`TreasuryFeeShare`].
+///
+/// Transaction fees and tips arrive through [`OnUnbalanced::on_unbalanceds`], XCM execution fees
+/// through [`OnUnbalanced::on_unbalanced`]. Both are split the same way.
+pub struct DealWithFees;
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+    fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+        let share = u32::from(TreasuryFeeShare::get().deconstruct());
+        let (to_treasury, to_author) = amount.ration(share, 100 - share);
+        <Treasury as OnUnbalanced<_>>::on_unbalanced(to_treasury);
+        <ToAuthor<Runtime> as OnUnbalanced<_>>::on_unbalanced(to_author);
+    }
+
+    fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance>) {
+        if let Some(mut fees) = fees_then_tips.next() {
+            if let Some(tips) = fees_then_tips.next() {
+                tips.merge_into(&mut fees);
+            }
+            Self::on_unbalanced(fees);
+        }
+    }
+}
+
 impl pallet_transaction_payment::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
-    type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, ()>;
+    type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, DealWithFees>;
     type WeightToFee = WeightToFee;
     type LengthToFee = ConstantMultiplier<Balance, TransactionByteFee>;
     type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
     type OperationalFeeMultiplier = ConstU8<5>;
 }
 
-impl pallet_sudo::Config for Runtime {
+parameter_types! {
+    pub const AssetDeposit: Balance = deposit(1, 190);
+    pub const AssetAccountDeposit: Balance = deposit(1, 16);
+    pub const ApprovalDeposit: Balance = EXISTENTIAL_DEPOSIT;
+    pub const AssetsStringLimit: u32 = 50;
+    // Key = 32 bytes, Value = 36 bytes (32+1+1+1+1)
+    pub const MetadataDepositBase: Balance = deposit(1, 68);
+    pub const MetadataDepositPerByte: Balance = deposit(0, 1);
+}
+
+/// Assets issued on this chain, created by anyone who pays the deposit.
+pub type LocalAssetsInstance = pallet_assets::Instance1;
+
+impl pallet_assets::Config<LocalAssetsInstance> for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type AssetId = u32;
+    type AssetIdParameter = codec::Compact<u32>;
+    type Currency = Balances;
+    type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type ForceOrigin = GeneralAdminOrRoot;
+    type AssetDeposit = AssetDeposit;
+    type AssetAccountDeposit = AssetAccountDeposit;
+    type MetadataDepositBase = MetadataDepositBase;
+    type MetadataDepositPerByte = MetadataDepositPerByte;
+    type ApprovalDeposit = ApprovalDeposit;
+    type StringLimit = AssetsStringLimit;
+    type Freezer = ();
+    type Extra = ();
+    type CallbackHandle = ();
+    type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+    type RemoveItemsLimit = ConstU32<1000>;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ();
+}
+
+/// Assets of other consensus systems, such as the relay chain token, identified by their location.
+/// The relay chain token arrives over XCM through [`xcm_config::RelayTokenTransactor`], the assets
+/// of siblings through [`xcm_config::ForeignAssetsTransactor`].
+///
+/// They are registered by governance, which also decides whether an asset is sufficient and at
+/// which rate it pays for fees, see [`AssetTxPayment`].
+pub type ForeignAssetsInstance = pallet_assets::Instance2;
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct ForeignAssetBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_assets::BenchmarkHelper<xcm::v3::Location> for ForeignAssetBenchmarkHelper {
+    fn create_asset_id_parameter(id: u32) -> xcm::v3::Location {
+        xcm::v3::Location::new(1, xcm::v3::Junctions::X1(xcm::v3::Junction::Parachain(id)))
+    }
+}
+
+impl pallet_assets::Config<ForeignAssetsInstance> for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type AssetId = xcm::v3::Location;
+    type AssetIdParameter = xcm::v3::Location;
+    type Currency = Balances;
+    // Only `force_create` registers foreign assets.
+    type CreateOrigin = AsEnsureOriginWithArg<EnsureNever<AccountId>>;
+    type ForceOrigin = GeneralAdminOrRoot;
+    type AssetDeposit = AssetDeposit;
+    type AssetAccountDeposit = AssetAccountDeposit;
+    type MetadataDepositBase = MetadataDepositBase;
+    type MetadataDepositPerByte = MetadataDepositPerByte;
+    type ApprovalDeposit = ApprovalDeposit;
+    type StringLimit = AssetsStringLimit;
+    type Freezer = ();
+    type Extra = ();
+    type CallbackHandle = ();
+    type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+    type RemoveItemsLimit = ConstU32<1000>;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ForeignAssetBenchmarkHelper;
+}
+
+/// Splits fees paid in foreign assets between the treasury and the block author, like
+/// [`DealWithFees`] does for the native token.
+pub struct DealWithAssetFees;
+impl HandleCredit<AccountId, ForeignAssets> for DealWithAssetFees {
+    fn handle_credit(credit: fungibles::Credit<AccountId, ForeignAssets>) {
+        // Asset imbalances have no `ration`, so split off the treasury share, rounded down like
+        // `ration` does.
+        let treasury_share = TreasuryFeeShare::get().mul_floor(credit.peek());
+        let (to_treasury, to_author) = credit.split(treasury_share);
+        // Fee assets are sufficient, so resolving only fails for amounts below the minimum
+        // balance. Those are burned.
+        let _ = <ForeignAssets as Balanced<_>>::resolve(&Treasury::account_id(), to_treasury);
+        if let Some(author) = Authorship::author() {
+            let _ = <ForeignAssets as Balanced<_>>::resolve(&author, to_author);
+        }
+    }
+}
+
+/// Converts native balances into balances of sufficient foreign assets, at the ratio of the
+/// minimum balance of the asset to the existential deposit. Governance configures the rate of an
+/// asset by setting its minimum balance with `force_asset_status`.
+pub type NativeToForeignAssetBalance =
+    BalanceToAssetBalance<Balances, Runtime, ConvertInto, ForeignAssetsInstance>;
+
+/// Lets transaction fees be paid in sufficient foreign assets, such as the relay chain token, at
+/// the rate of [`NativeToForeignAssetBalance`].
+impl pallet_asset_tx_payment::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Fungibles = ForeignAssets;
+    type OnChargeAssetTransaction =
+        pallet_asset_tx_payment::FungiblesAdapter<NativeToForeignAssetBalance, DealWithAssetFees>;
+}
+
+parameter_types! {
+    pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+    pub TreasuryAccount: AccountId = Treasury::account_id();
+    pub const ProposalBond: Permill = Permill::from_percent(5);
+    pub const ProposalBondMinimum: Balance = 100 * UNIT;
+    pub const ProposalBondMaximum: Balance = 500 * UNIT;
+    pub const SpendPeriod: BlockNumber = 6 * DAYS;
+    pub const Burn: Permill = Permill::from_percent(1);
+    pub const PayoutSpendPeriod: BlockNumber = 30 * DAYS;
+    pub const MaxApprovals: u32 = 100;
+}
+
+/// Creates the arguments of treasury spends in benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct TreasuryBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_treasury::ArgumentsFactory<(), AccountId> for TreasuryBenchmarkHelper {
+    fn create_asset_kind(_seed: u32) {}
+
+    fn create_beneficiary(seed: [u8; 32]) -> AccountId {
+        seed.into()
+    }
+}
+
+impl pallet_treasury::Config for Runtime {
+    type PalletId = TreasuryPalletId;
+    type Currency = Balances;
+    type ApproveOrigin = EitherOf<EnsureRoot<AccountId>, Treasurer>;
+    type RejectOrigin = EitherOf<EnsureRoot<AccountId>, Treasurer>;
+    type RuntimeEvent = RuntimeEvent;
+    type OnSlash = Treasury;
+    type ProposalBond = ProposalBond;
+    type ProposalBondMinimum = ProposalBondMinimum;
+    type ProposalBondMaximum = ProposalBondMaximum;
+    type SpendPeriod = SpendPeriod;
+    type Burn = Burn;
+    type BurnDestination = ();
+    type SpendFunds = ();
+    type MaxApprovals = MaxApprovals;
+    type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+    type SpendOrigin = TreasurySpender;
+    // Only the native token is paid out, from the treasury account.
+    type AssetKind = ();
+    type Beneficiary = AccountId;
+    type BeneficiaryLookup = IdentityLookup<AccountId>;
+    type Paymaster = PayFromAccount<Balances, TreasuryAccount>;
+    type BalanceConverter = UnityAssetBalanceConversion;
+    type PayoutPeriod = PayoutSpendPeriod;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = TreasuryBenchmarkHelper;
+}
+
+parameter_types! {
+    pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) *
+        RuntimeBlockWeights::get().max_block;
+    pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Config for Runtime {
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeEvent = RuntimeEvent;
+    type PalletsOrigin = OriginCaller;
+    type RuntimeCall = RuntimeCall;
+    type MaximumWeight = MaximumSchedulerWeight;
+    // Referenda schedule their enactment through the pallet directly, this is for governance
+    // calls that schedule other calls.
+    type ScheduleOrigin = governance::ScheduleOrigin;
+    type MaxScheduledPerBlock = MaxScheduledPerBlock;
+    type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+    type OriginPrivilegeCmp = governance::OriginPrivilegeCmp;
+    type Preimages = Preimage;
+}
+
+parameter_types! {
+    pub const PreimageBaseDeposit: Balance = deposit(2, 64);
+    // Bytes are charged at a six-thousandth of `deposit(0, 1)`. Runtime upgrades are noted as
+    // preimages of a few MB. At the storage price a 2 MB upgrade would hold about 12.6M UNIT,
+    // more than any account is endowed with. At this price it holds about 2100 UNIT, which still
+    // makes filling storage with large preimages expensive. Noting one is only needed until its
+    // referendum is over, unnoting it then returns the deposit. The base deposit is charged at the
+    // storage price, so many small preimages get no discount.
+    pub const PreimageByteDeposit: Balance = MILLIUNIT;
+    pub const PreimageHoldReason: RuntimeHoldReason =
+        RuntimeHoldReason::Preimage(pallet_preimage::HoldReason::Preimage);
+}
+
+impl pallet_preimage::Config for Runtime {
+    type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type ManagerOrigin = EnsureRoot<AccountId>;
+    type Consideration = HoldConsideration<
+        AccountId,
+        Balances,
+        PreimageHoldReason,
+        LinearStoragePrice<PreimageBaseDeposit, PreimageByteDeposit, Balance>,
+    >;
+}
+
+parameter_types! {
+    // Minimum encoded size of a `Registration` without its `IdentityInfo`, which is charged by
+    // the byte.
+    pub const BasicDeposit: Balance = deposit(1, 17);
+    pub const ByteDeposit: Balance = deposit(0, 1);
+    // One storage item; key size 32, value size 16 + 4 + 32 bytes.
+    pub const SubAccountDeposit: Balance = deposit(1, 53);
+    pub const MaxAdditionalFields: u32 = 100;
+}
+
+impl pallet_identity::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type BasicDeposit = BasicDeposit;
+    type ByteDeposit = ByteDeposit;
+    type SubAccountDeposit = SubAccountDeposit;
+    type MaxSubAccounts = ConstU32<100>;
+    type IdentityInformation = pallet_identity::legacy::IdentityInfo<MaxAdditionalFields>;
+    type MaxRegistrars = ConstU32<20>;
+    type Slashed = Treasury;
+    type ForceOrigin = GeneralAdminOrRoot;
+    type RegistrarOrigin = GeneralAdminOrRoot;
+    type OffchainSignature = Signature;
+    type SigningPublicKey = <Signature as Verify>::Signer;
+    type UsernameAuthorityOrigin = GeneralAdminOrRoot;
+    type PendingUsernameExpiration = ConstU32<{ 7 * DAYS }>;
+    type MaxSuffixLength = ConstU32<7>;
+    type MaxUsernameLength = ConstU32<32>;
+    type WeightInfo = pallet_identity::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    pub const MinVestedTransfer: Balance = UNIT;
+    // `pallet_balances` freezes locked funds for every withdraw reason, so whatever is allowed
+    // here, unvested funds can't be reserved and can't pay the collator candidacy bond. Locks
+    // overlap with the vesting lock, so unvested funds can vote, and could be bonded by a staking
+    // pallet that bonds with a lock.
+    pub UnvestedFundsAllowedWithdrawReasons: WithdrawReasons =
+        WithdrawReasons::except(WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE);
+}
+
+impl pallet_vesting::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type BlockNumberToBalance = ConvertInto;
+    type MinVestedTransfer = MinVestedTransfer;
+    type WeightInfo = pallet_vesting::weights::SubstrateWeight<Runtime>;
+    type UnvestedFundsAllowedWithdrawReasons = UnvestedFundsAllowedWithdrawReasons;
+    type BlockNumberProvider = System;
+    const MAX_VESTING_SCHEDULES: u32 = 28;
+}
+
+impl pallet_utility::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type PalletsOrigin = OriginCaller;
+    type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    // One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
+    pub const DepositBase: Balance = deposit(1, 88);
+    // Additional storage item size of 32 bytes.
+    pub const DepositFactor: Balance = deposit(0, 32);
+}
+
+impl pallet_multisig::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type RuntimeCall = RuntimeCall;
-    type WeightInfo = ();
+    type Currency = Balances;
+    type DepositBase = DepositBase;
+    type DepositFactor = DepositFactor;
+    type MaxSignatories = ConstU32<100>;
+    type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+    // One storage item; key size 32, value size 8.
+    pub const ProxyDepositBase: Balance = deposit(1, 8);
+    // Additional storage item size of 33 bytes.
+    pub const ProxyDepositFactor: Balance = deposit(0, 33);
+    // One storage item; key size 32, value size 8.
+    pub const AnnouncementDepositBase: Balance = deposit(1, 8);
+    // Additional storage item size of 2 * 33 bytes.
+    pub const AnnouncementDepositFactor: Balance = deposit(0, 66);
+}
+
+/// The calls a proxy account may make on behalf of the account it proxies.
+///
+/// Validator operators keep the stash key cold and run daily operations from hot proxies that can
+/// only touch what their type allows.
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Encode,
+    Decode,
+    Default,
+    RuntimeDebug,
+    MaxEncodedLen,
+    scale_info::TypeInfo,
+)]
+pub enum ProxyType {
+    /// All calls.
+    #[default]
+    Any,
+    /// All calls that can't move funds out of the account.
+    NonTransfer,
+    /// Voting, referenda and treasury proposals.
+    Governance,
+    /// Collator registration and session keys.
+    Staking,
+    /// Reserved for the nomination pools, which are not part of the runtime yet. Until then
+    /// only utility calls pass, so that a proxy of this type can't do anything on its own.
+    NominationPools,
+    /// Rejecting announcements of time-delayed proxies.
+    CancelProxy,
+}
+
+impl InstanceFilter<RuntimeCall> for ProxyType {
+    fn filter(&self, c: &RuntimeCall) -> bool {
+        match self {
+            ProxyType::Any => true,
+            ProxyType::NonTransfer => matches!(
+                c,
+                RuntimeCall::System(..)
+                    // Vesting, but not transferring vested funds.
+                    | RuntimeCall::Vesting(pallet_vesting::Call::vest { .. })
+                    | RuntimeCall::Vesting(pallet_vesting::Call::vest_other { .. })
+                    | RuntimeCall::Scheduler(..)
+                    | RuntimeCall::Preimage(..)
+                    | RuntimeCall::Treasury(..)
+                    | RuntimeCall::CollatorSelection(..)
+                    | RuntimeCall::Session(..)
+                    | RuntimeCall::ConvictionVoting(..)
+                    | RuntimeCall::Referenda(..)
+                    | RuntimeCall::Whitelist(..)
+                    | RuntimeCall::Utility(..)
+                    | RuntimeCall::Proxy(..)
+                    | RuntimeCall::Multisig(..)
+                    | RuntimeCall::Identity(..)
+            ),
+            ProxyType::Governance => matches!(
+                c,
+                RuntimeCall::Treasury(..)
+                    | RuntimeCall::ConvictionVoting(..)
+                    | RuntimeCall::Referenda(..)
+                    | RuntimeCall::Whitelist(..)
+                    | RuntimeCall::Utility(..)
+            ),
+            ProxyType::Staking => matches!(
+                c,
+                RuntimeCall::CollatorSelection(..)
+                    | RuntimeCall::Session(..)
+                    | RuntimeCall::Utility(..)
+            ),
+            ProxyType::NominationPools => matches!(c, RuntimeCall::Utility(..)),
+            ProxyType::CancelProxy => matches!(
+                c,
+                RuntimeCall::Proxy(pallet_proxy::Call::reject_announcement { .. })
+                    | RuntimeCall::Utility(..)
+                    | RuntimeCall::Multisig(..)
+            ),
+        }
+    }
+
+    fn is_superset(&self, o: &Self) -> bool {
+        match (self, o) {
+            (x, y) if x == y => true,
+            (ProxyType::Any, _) => true,
+            (_, ProxyType::Any) => false,
+            (ProxyType::NonTransfer, _) => true,
+            _ => false,
+        }
+    }
+}
+
+impl pallet_proxy::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type RuntimeCall = RuntimeCall;
+    type Currency = Balances;
+    type ProxyType = ProxyType;
+    type ProxyDepositBase = ProxyDepositBase;
+    type ProxyDepositFactor = ProxyDepositFactor;
+    type MaxProxies = ConstU32<32>;
+    type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+    type MaxPending = ConstU32<32>;
+    type CallHasher = BlakeTwo256;
+    type AnnouncementDepositBase = AnnouncementDepositBase;
+    type AnnouncementDepositFactor = AnnouncementDepositFactor;
 }
 
 parameter_types! {
@@ -464,7 +927,15 @@ parameter_types! {
     pub const RelayOrigin: AggregateMessageOrigin = AggregateMessageOrigin::Parent;
 }
 
-pub struct PalletBabeConsensusHook {}
+impl pallet_relay_chain_slot::Config for Runtime {}
+
+/// Consensus hook for BABE.
+///
+/// Our blocks are authored on BABE slots which are independent of the relay chain, so the hook
+/// only ensures that a block doesn't claim a slot before the one of its relay parent. The relay
+/// slot is converted with the slot duration the chain spec set in `RelayChainSlot`, a wrong value
+/// for it is caught here. The node refuses to start if it doesn't match the relay chain.
+pub struct PalletBabeConsensusHook;
 impl cumulus_pallet_parachain_system::ConsensusHook for PalletBabeConsensusHook {
     fn on_state_proof(
         state_proof: &cumulus_pallet_parachain_system::relay_state_snapshot::RelayChainStateProof,
@@ -472,13 +943,35 @@ impl cumulus_pallet_parachain_system::ConsensusHook for PalletBabeConsensusHook
         Weight,
         cumulus_pallet_parachain_system::consensus_hook::UnincludedSegmentCapacity,
     ) {
-        // TODO: Implement the consensus hook of for the Pallet BABE in cumulus
-        unimplemented!();
+        let relay_chain_slot = state_proof
+            .read_slot()
+            .expect("failed to read relay chain slot");
+        let relay_slot_duration = u64::from(RelayChainSlot::slot_duration_millis());
+        let para_slot_from_relay = u64::from(relay_chain_slot)
+            .checked_mul(relay_slot_duration)
+            .map(|timestamp| sp_consensus_babe::Slot::from(timestamp / SLOT_DURATION))
+            .expect("relay chain slot timestamp does not overflow; qed");
+
+        // `CurrentSlot` was set from the BABE pre-digest in `on_initialize`.
+        let para_slot = Babe::current_slot();
+        assert!(
+            para_slot >= para_slot_from_relay,
+            "BABE slot {:?} is before the slot of the relay parent {:?}, is \
+             the relay chain slot duration in the chain spec correct?",
+            para_slot,
+            para_slot_from_relay,
+        );
+
+        let weight = <Runtime as frame_system::Config>::DbWeight::get().reads(2);
+        let capacity = core::num::NonZeroU32::new(UNINCLUDED_SEGMENT_CAPACITY.max(1))
+            .expect("1 is the minimum value and non-zero; qed");
+
+        (weight, capacity.into())
     }
 }
 
 impl cumulus_pallet_parachain_system::Config for Runtime {
-    type WeightInfo = ();
+    type WeightInfo = weights::cumulus_pallet_parachain_system::WeightInfo<Runtime>;
     type RuntimeEvent = RuntimeEvent;
     type OnSystemEvent = ();
     type SelfParaId = parachain_info::Pallet<Runtime>;
@@ -499,7 +992,7 @@ parameter_types! {
 
 impl pallet_message_queue::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
-    type WeightInfo = ();
+    type WeightInfo = weights::pallet_message_queue::WeightInfo<Runtime>;
     #[cfg(feature = "runtime-benchmarks")]
     type MessageProcessor = pallet_message_queue::mock_helpers::NoopMessageProcessor<
         cumulus_primitives_core::AggregateMessageOrigin,
@@ -526,15 +1019,15 @@ impl cumulus_pallet_xcmp_queue::Config for Runtime {
     // Enqueue XCMP messages from siblings for later processing.
     type XcmpQueue = TransformOrigin<MessageQueue, AggregateMessageOrigin, ParaId, ParaIdToSibling>;
     type MaxInboundSuspended = sp_core::ConstU32<1_000>;
-    type ControllerOrigin = EnsureRoot<AccountId>;
+    type ControllerOrigin = GeneralAdminOrRoot;
     type ControllerOriginConverter = XcmOriginToTransactDispatchOrigin;
-    type WeightInfo = ();
+    type WeightInfo = weights::cumulus_pallet_xcmp_queue::WeightInfo<Runtime>;
     type PriceForSiblingDelivery = NoPriceForMessageDelivery<ParaId>;
 }
 
 parameter_types! {
+    /// Number of blocks after which a collator that didn't author is kicked.
     pub const Period: u32 = 6 * HOURS;
-    pub const Offset: u32 = 0;
 }
 
 impl pallet_session::Config for Runtime {
@@ -542,13 +1035,15 @@ impl pallet_session::Config for Runtime {
     type ValidatorId = <Self as frame_system::Config>::AccountId;
     // we don't have stash and controller, thus we don't need the convert as well.
     type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
-    type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
-    type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
+    // Sessions end with the BABE epoch, so the authority set only ever changes on epoch
+    // boundaries. Chains that ended sessions by period are moved over by `migrations::v2`.
+    type ShouldEndSession = Babe;
+    type NextSessionRotation = Babe;
     type SessionManager = CollatorSelection;
     // Essentially just Babe , but let's be pedantic.
     type SessionHandler = <SessionKeys as sp_runtime::traits::OpaqueKeys>::KeyTypeIdProviders;
     type Keys = SessionKeys;
-    type WeightInfo = ();
+    type WeightInfo = weights::pallet_session::WeightInfo<Runtime>;
 }
 
 parameter_types! {
@@ -558,9 +1053,10 @@ parameter_types! {
     pub const StakingAdminBodyId: BodyId = BodyId::Defense;
 }
 
-/// We allow root and the StakingAdmin to execute privileged collator selection operations.
+/// We allow root, the staking admin track and the StakingAdmin body of the relay chain to execute
+/// privileged collator selection operations.
 pub type CollatorSelectionUpdateOrigin = EitherOfDiverse<
-    EnsureRoot<AccountId>,
+    StakingAdminOrRoot,
     EnsureXcm<IsVoiceOfBody<RelayLocation, StakingAdminBodyId>>,
 >;
 
@@ -577,7 +1073,7 @@ impl pallet_collator_selection::Config for Runtime {
     type ValidatorId = <Self as frame_system::Config>::AccountId;
     type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
     type ValidatorRegistration = Session;
-    type WeightInfo = ();
+    type WeightInfo = weights::pallet_collator_selection::WeightInfo<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -588,13 +1084,19 @@ construct_runtime!(
         ParachainSystem: cumulus_pallet_parachain_system = 1,
         Timestamp: pallet_timestamp = 2,
         ParachainInfo: parachain_info = 3,
+        RelayChainSlot: pallet_relay_chain_slot = 4,
 
         // Monetary stuff.
         Balances: pallet_balances = 10,
         TransactionPayment: pallet_transaction_payment = 11,
+        Treasury: pallet_treasury = 12,
+        Vesting: pallet_vesting = 13,
+        AssetTxPayment: pallet_asset_tx_payment = 14,
 
-        // Governance
-        Sudo: pallet_sudo = 15,
+        // Governance. Index 15 belonged to Sudo, it is not reused so that calls signed for Sudo
+        // can't be mistaken for calls of another pallet.
+        Scheduler: pallet_scheduler = 16,
+        Preimage: pallet_preimage = 17,
 
         // Collator support. The order of these 4 are important and shall not change.
         Authorship: pallet_authorship = 20,
@@ -613,6 +1115,22 @@ construct_runtime!(
         Historical: pallet_session::historical::{Pallet} = 36,
         Offences: pallet_offences = 37,
         // Staking: pallet_staking,
+
+        // OpenGov
+        ConvictionVoting: pallet_conviction_voting = 40,
+        Referenda: pallet_referenda = 41,
+        Origins: pallet_custom_origins = 42,
+        Whitelist: pallet_whitelist = 43,
+
+        // Account management.
+        Utility: pallet_utility = 50,
+        Multisig: pallet_multisig = 51,
+        Proxy: pallet_proxy = 52,
+        Identity: pallet_identity = 53,
+
+        // Assets.
+        Assets: pallet_assets::<Instance1> = 60,
+        ForeignAssets: pallet_assets::<Instance2> = 61,
     }
 );
 
@@ -623,12 +1141,28 @@ mod benches {
         [pallet_balances, Balances]
         [pallet_session, SessionBench::<Runtime>]
         [pallet_timestamp, Timestamp]
-        [pallet_offences, Offences],
         [pallet_message_queue, MessageQueue]
-        [pallet_sudo, Sudo]
+        [pallet_scheduler, Scheduler]
+        [pallet_preimage, Preimage]
         [pallet_collator_selection, CollatorSelection]
         [cumulus_pallet_parachain_system, ParachainSystem]
         [cumulus_pallet_xcmp_queue, XcmpQueue]
+        [pallet_xcm, PalletXcmExtrinsicsBenchmark::<Runtime>]
+        [pallet_xcm_benchmarks::fungible, XcmBalances]
+        [pallet_xcm_benchmarks::generic, XcmGeneric]
+        [pallet_babe, Babe]
+        [pallet_grandpa, Grandpa]
+        [pallet_conviction_voting, ConvictionVoting]
+        [pallet_referenda, Referenda]
+        [pallet_whitelist, Whitelist]
+        [pallet_treasury, Treasury]
+        [pallet_vesting, Vesting]
+        [pallet_utility, Utility]
+        [pallet_multisig, Multisig]
+        [pallet_proxy, Proxy]
+        [pallet_identity, Identity]
+        [pallet_assets, Assets]
+        [pallet_assets, ForeignAssets]
     );
 }
 
@@ -845,6 +1379,31 @@ impl_runtime_apis! {
         }
     }
 
+    impl weights::DbWeightApi<Block> for Runtime {
+        fn db_weight_backend() -> Vec<u8> {
+            DB_WEIGHT_BACKEND.as_bytes().to_vec()
+        }
+    }
+
+    impl genesis_config_presets::GenesisPresetsApi<Block> for Runtime {
+        fn get_preset(id: genesis_config_presets::PresetId) -> Option<Vec<u8>> {
+            genesis_config_presets::get_preset(core::str::from_utf8(&id).ok()?)
+        }
+
+        fn preset_names() -> Vec<genesis_config_presets::PresetId> {
+            genesis_config_presets::preset_names()
+                .into_iter()
+                .map(|name| name.as_bytes().to_vec())
+                .collect()
+        }
+    }
+
+    impl staking_api::StakingApi<Block, AccountId> for Runtime {
+        fn validators() -> Vec<staking_api::ValidatorInfo<AccountId>> {
+            staking_api::validators()
+        }
+    }
+
     impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
         fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
             ParachainSystem::collect_collation_info(header)
@@ -880,6 +1439,9 @@ impl_runtime_apis! {
             use frame_support::traits::StorageInfoTrait;
             use frame_system_benchmarking::Pallet as SystemBench;
             use cumulus_pallet_session_benchmarking::Pallet as SessionBench;
+            use pallet_xcm::benchmarking::Pallet as PalletXcmExtrinsicsBenchmark;
+            use pallet_xcm_benchmarks::fungible::Pallet as XcmBalances;
+            use pallet_xcm_benchmarks::generic::Pallet as XcmGeneric;
 
             let mut list = Vec::<BenchmarkList>::new();
             list_benchmarks!(list, extra);
@@ -908,6 +1470,225 @@ impl_runtime_apis! {
             use cumulus_pallet_session_benchmarking::Pallet as SessionBench;
             impl cumulus_pallet_session_benchmarking::Config for Runtime {}
 
+            use pallet_xcm::benchmarking::Pallet as PalletXcmExtrinsicsBenchmark;
+            use xcm::latest::prelude::{
+                Asset, AssetId, Assets, Fungible, Here, InteriorLocation, Junction, Location,
+                NetworkId, Parent, Response,
+            };
+
+            parameter_types! {
+                pub ExistentialDepositAsset: Option<Asset> = Some((
+                    Here,
+                    ExistentialDeposit::get()
+                ).into());
+                /// The sibling parachain the benchmarks send to.
+                pub RandomParaId: ParaId = ParaId::new(43211234);
+            }
+
+            /// Delivers to the relay chain and to `RandomParaId`, opening the channel to it.
+            type DeliveryHelper = (
+                cumulus_primitives_utility::ToParentDeliveryHelper<
+                    xcm_config::XcmConfig,
+                    ExistentialDepositAsset,
+                    (),
+                >,
+                polkadot_runtime_common::xcm_sender::ToParachainDeliveryHelper<
+                    xcm_config::XcmConfig,
+                    ExistentialDepositAsset,
+                    NoPriceForMessageDelivery<ParaId>,
+                    RandomParaId,
+                    ParachainSystem,
+                >,
+            );
+
+            /// The location of `RandomParaId`.
+            fn sibling() -> Location {
+                Location::new(1, [Junction::Parachain(RandomParaId::get().into())])
+            }
+
+            impl pallet_xcm::benchmarking::Config for Runtime {
+                type DeliveryHelper = DeliveryHelper;
+
+                fn reachable_dest() -> Option<Location> {
+                    Some(Parent.into())
+                }
+
+                fn teleportable_asset_and_dest() -> Option<(Asset, Location)> {
+                    // The benchmark funds the caller in `Balances` and checks that the transfer
+                    // spends it, so it can only teleport the native token, which isn't teleported
+                    // anywhere. The relay chain token is teleported from `ForeignAssets`, and its
+                    // instructions are benchmarked in `pallet_xcm_benchmarks::fungible`.
+                    None
+                }
+
+                fn reserve_transferable_asset_and_dest() -> Option<(Asset, Location)> {
+                    // The native token is reserve transferred to a sibling, with this chain as
+                    // its reserve.
+                    Some((native_token(UNIT), sibling()))
+                }
+
+                fn get_asset() -> Asset {
+                    native_token(ExistentialDeposit::get())
+                }
+            }
+
+            use pallet_xcm_benchmarks::fungible::Pallet as XcmBalances;
+            use pallet_xcm_benchmarks::generic::Pallet as XcmGeneric;
+
+            /// The native token, which pays for execution in the generic XCM benchmarks.
+            fn native_token(amount: Balance) -> Asset {
+                Asset {
+                    id: AssetId(Here.into()),
+                    fun: Fungible(amount),
+                }
+            }
+
+            /// An asset of `RandomParaId`, registered in `ForeignAssets`.
+            fn sibling_token(amount: Balance) -> Asset {
+                use frame_support::traits::fungibles::{Create, Inspect};
+
+                let id = xcm::v3::Location::new(
+                    1,
+                    xcm::v3::Junctions::X1(xcm::v3::Junction::Parachain(
+                        RandomParaId::get().into(),
+                    )),
+                );
+                if !ForeignAssets::asset_exists(id) {
+                    <ForeignAssets as Create<AccountId>>::create(
+                        id,
+                        TreasuryAccount::get(),
+                        true,
+                        ExistentialDeposit::get(),
+                    )
+                    .expect("the sibling token is not registered yet");
+                }
+                Asset {
+                    id: AssetId(sibling()),
+                    fun: Fungible(amount),
+                }
+            }
+
+            /// The relay chain token, the asset the fungible XCM benchmarks move around. It is
+            /// registered in `ForeignAssets` first, like governance does on a live chain.
+            fn relay_token(amount: Balance) -> Asset {
+                use frame_support::traits::fungibles::{Create, Inspect};
+
+                let id = xcm_config::RelayTokenId::get();
+                if !ForeignAssets::asset_exists(id) {
+                    <ForeignAssets as Create<AccountId>>::create(
+                        id,
+                        TreasuryAccount::get(),
+                        true,
+                        ExistentialDeposit::get(),
+                    )
+                    .expect("the relay chain token is not registered yet");
+                }
+                Asset {
+                    id: AssetId(RelayLocation::get()),
+                    fun: Fungible(amount),
+                }
+            }
+
+            parameter_types! {
+                pub CheckedAccount: Option<(AccountId, xcm_builder::MintLocation)> =
+                    cfg!(feature = "relay-teleports").then(|| (
+                        xcm_config::CheckingAccount::get(),
+                        xcm_builder::MintLocation::NonLocal,
+                    ));
+                pub TrustedTeleporter: Option<(Location, Asset)> = cfg!(feature = "relay-teleports")
+                    .then(|| (RelayLocation::get(), relay_token(UNIT)));
+                // The relay chain is no reserve with `relay-teleports`, siblings are in both
+                // builds.
+                pub TrustedReserve: Option<(Location, Asset)> =
+                    Some((sibling(), sibling_token(UNIT)));
+            }
+
+            impl pallet_xcm_benchmarks::Config for Runtime {
+                type XcmConfig = xcm_config::XcmConfig;
+                type AccountIdConverter = xcm_config::LocationToAccountId;
+                type DeliveryHelper = DeliveryHelper;
+
+                fn valid_destination() -> Result<Location, BenchmarkError> {
+                    Ok(RelayLocation::get())
+                }
+
+                fn worst_case_holding(_depositable_count: u32) -> Assets {
+                    vec![
+                        native_token(1_000_000 * UNIT),
+                        relay_token(1_000_000 * UNIT),
+                    ]
+                    .into()
+                }
+            }
+
+            // Fungible instructions are benchmarked on the relay chain token in `ForeignAssets`,
+            // which costs more than the native token in `Balances`.
+            impl pallet_xcm_benchmarks::fungible::Config for Runtime {
+                type TransactAsset = frame_support::traits::fungible::ItemOf<
+                    ForeignAssets,
+                    xcm_config::RelayTokenId,
+                    AccountId,
+                >;
+                type CheckedAccount = CheckedAccount;
+                type TrustedTeleporter = TrustedTeleporter;
+                type TrustedReserve = TrustedReserve;
+
+                fn get_asset() -> Asset {
+                    relay_token(UNIT)
+                }
+            }
+
+            impl pallet_xcm_benchmarks::generic::Config for Runtime {
+                type TransactAsset = Balances;
+                type RuntimeCall = RuntimeCall;
+
+                fn worst_case_response() -> (u64, Response) {
+                    (0u64, Response::Version(Default::default()))
+                }
+
+                fn worst_case_asset_exchange() -> Result<(Assets, Assets), BenchmarkError> {
+                    Err(BenchmarkError::Skip)
+                }
+
+                fn universal_alias() -> Result<(Location, Junction), BenchmarkError> {
+                    Err(BenchmarkError::Skip)
+                }
+
+                fn transact_origin_and_runtime_call(
+                ) -> Result<(Location, RuntimeCall), BenchmarkError> {
+                    // An empty batch is the cheapest call `SafeCallFilter` lets through.
+                    Ok((
+                        RelayLocation::get(),
+                        pallet_utility::Call::batch { calls: vec![] }.into(),
+                    ))
+                }
+
+                fn subscribe_origin() -> Result<Location, BenchmarkError> {
+                    Ok(RelayLocation::get())
+                }
+
+                fn claimable_asset() -> Result<(Location, Location, Assets), BenchmarkError> {
+                    Ok((RelayLocation::get(), Here.into(), native_token(UNIT).into()))
+                }
+
+                fn fee_asset() -> Result<Asset, BenchmarkError> {
+                    Ok(native_token(1_000_000 * UNIT))
+                }
+
+                fn unlockable_asset() -> Result<(Location, Location, Asset), BenchmarkError> {
+                    Err(BenchmarkError::Skip)
+                }
+
+                fn export_message_origin_and_destination(
+                ) -> Result<(Location, NetworkId, InteriorLocation), BenchmarkError> {
+                    Err(BenchmarkError::Skip)
+                }
+
+                fn alias_origin() -> Result<(Location, Location), BenchmarkError> {
+                    Err(BenchmarkError::Skip)
+                }
+            }
+
             use frame_support::traits::WhitelistedStorageKeys;
             let whitelist = AllPalletsWithSystem::whitelisted_storage_keys();
 
@@ -920,6 +1701,9 @@ impl_runtime_apis! {
         }
     }
 
+    // `get_preset` and `preset_names` only come with `sp-genesis-builder` 0.12, which needs the
+    // polkadot-sdk 1.11 crates. Until the runtime moves to them, the node reads the presets
+    // through `GenesisPresetsApi` above.
     impl sp_genesis_builder::GenesisBuilder<Block> for Runtime {
         fn create_default_config() -> Vec<u8> {
             create_default_config::<RuntimeGenesisConfig>()
@@ -931,7 +1715,6 @@ impl_runtime_apis! {
     }
 }
 
-/// TODO: Temporarily declare the function signature, need to implement for pallet BABE
 /// The block executor used when validating a PoV at the relay chain.
 ///
 /// When executing the block it will verify the block seal to ensure that the correct author created
@@ -945,8 +1728,53 @@ where
     I: ExecuteBlock<Block>,
 {
     fn execute_block(block: Block) {
+        use frame_support::traits::Get;
+        use sp_consensus_babe::digests::CompatibleDigestItem;
+        use sp_runtime::{traits::Header as _, RuntimeAppPublic};
+
         let (mut header, extrinsics) = block.deconstruct();
-        unimplemented!();
+
+        let mut seal = None;
+        header
+            .digest_mut()
+            .logs
+            .retain(|item| match (item.as_babe_seal(), seal.is_some()) {
+                (Some(_), true) => panic!("Found multiple BABE seal digests"),
+                (None, _) => true,
+                (Some(signature), false) => {
+                    seal = Some(signature);
+                    false
+                }
+            });
+        let seal = seal.expect("Could not find a BABE seal digest!");
+
+        let pre_digest = header
+            .digest()
+            .logs()
+            .iter()
+            .find_map(|item| item.as_babe_pre_digest())
+            .expect("Could not find a BABE pre-digest!");
+
+        // The authorities have to be read before the block is executed, as it may enact the next
+        // epoch. A block claiming a slot past the current epoch is authored by the next one.
+        let slot = pre_digest.slot();
+        let epoch = if *pallet_babe::Pallet::<T>::genesis_slot() != 0
+            && slot >= pallet_babe::Pallet::<T>::current_epoch_start() + T::EpochDuration::get()
+        {
+            pallet_babe::Pallet::<T>::next_epoch()
+        } else {
+            pallet_babe::Pallet::<T>::current_epoch()
+        };
+        let (author, _) = epoch
+            .authorities
+            .get(pre_digest.authority_index() as usize)
+            .expect("Invalid BABE authority index!");
+
+        if !author.verify(&header.hash(), &seal) {
+            panic!("Invalid BABE seal");
+        }
+
+        I::execute_block(Block::new(header, extrinsics));
     }
 }
 
@@ -0,0 +1,375 @@
+//! Storage migrations of the runtime.
+//!
+//! Every migration is versioned through [`VersionedMigration`], so it runs exactly once no matter
+//! how many runtime upgrades it stays listed in. Migrations that have been applied on all our
+//! networks are removed from [`Unreleased`] with the next release.
+
+use crate::{BabeId, GrandpaId, MaxAuthorities, Runtime, SessionKeys};
+use frame_support::{
+    migrations::{RemovePallet, VersionedMigration},
+    parameter_types,
+    traits::{CrateVersion, GetStorageVersion, PalletInfoAccess, StorageVersion},
+};
+
+/// Migrations that have not been applied on all of our networks yet.
+pub type Unreleased = (
+    v1::MigrateAuraToBabe,
+    v2::MigrateSessionsToBabeEpochs,
+    RemovePallet<AuraPalletName, <Runtime as frame_system::Config>::DbWeight>,
+    RemovePallet<AuraExtPalletName, <Runtime as frame_system::Config>::DbWeight>,
+    // Governance moved to referenda, this removes the sudo key along with the rest of the pallet.
+    RemovePallet<SudoPalletName, <Runtime as frame_system::Config>::DbWeight>,
+);
+
+parameter_types! {
+    pub const AuraPalletName: &'static str = "Aura";
+    pub const AuraExtPalletName: &'static str = "AuraExt";
+    pub const SudoPalletName: &'static str = "Sudo";
+}
+
+/// The storage version of the consensus setup of the runtime.
+///
+/// Migrations that change how blocks are authored touch the storage of several pallets, none of
+/// which declares a storage version it could be tracked by. They are versioned under this prefix
+/// instead.
+pub struct ConsensusStorageVersion;
+
+impl ConsensusStorageVersion {
+    /// The version the consensus storage has after all migrations in this module.
+    pub const IN_CODE: StorageVersion = StorageVersion::new(2);
+}
+
+impl PalletInfoAccess for ConsensusStorageVersion {
+    // Not a pallet, so there is no index in `construct_runtime!` to refer to.
+    fn index() -> usize {
+        u8::MAX as usize
+    }
+
+    fn name() -> &'static str {
+        "ConsensusStorageVersion"
+    }
+
+    fn name_hash() -> [u8; 16] {
+        sp_core::hashing::twox_128(Self::name().as_bytes())
+    }
+
+    fn module_name() -> &'static str {
+        module_path!()
+    }
+
+    fn crate_version() -> CrateVersion {
+        frame_support::crate_to_crate_version!()
+    }
+}
+
+impl GetStorageVersion for ConsensusStorageVersion {
+    type InCodeStorageVersion = StorageVersion;
+
+    fn in_code_storage_version() -> Self::InCodeStorageVersion {
+        Self::IN_CODE
+    }
+
+    fn on_chain_storage_version() -> StorageVersion {
+        StorageVersion::get::<Self>()
+    }
+}
+
+/// Take a chain that was authored with Aura by collator-selection collators over to BABE.
+///
+/// GRANDPA keys can't be derived from anything the collators had under Aura, so they have to be
+/// rotated in first: the runtime upgraded from has to run with [`v1::OldSessionKeys`], and every
+/// validator has to have set its GRANDPA key with them. The migration keeps those keys and only
+/// turns the Aura key into the BABE key. `pre_upgrade` fails if a validator is missing its
+/// GRANDPA key, so check the upgrade with `try-runtime` before enacting it.
+///
+/// Chains that started out with BABE have no Aura authorities and are left untouched. The move to
+/// staking storage follows once `pallet_staking` is part of the runtime; until then the collators
+/// stay managed by collator selection.
+pub mod v1 {
+    use super::*;
+    use frame_support::{
+        pallet_prelude::ValueQuery, storage_alias, traits::OnRuntimeUpgrade, weights::Weight,
+        WeakBoundedVec,
+    };
+    use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+    use sp_consensus_babe::BabeAuthorityWeight;
+    use sp_core::sr25519;
+    use sp_std::prelude::*;
+
+    #[cfg(feature = "try-runtime")]
+    use codec::{Decode, Encode};
+    #[cfg(feature = "try-runtime")]
+    use sp_runtime::TryRuntimeError;
+
+    const LOG_TARGET: &str = "runtime::migrations::v1";
+
+    sp_runtime::impl_opaque_keys! {
+        /// The session keys of the runtime while it was authored with Aura, with the GRANDPA key
+        /// rotated in ahead of the move to BABE.
+        pub struct OldSessionKeys {
+            pub aura: AuraId,
+            pub grandpa: GrandpaId,
+        }
+    }
+
+    #[storage_alias]
+    type AuraAuthorities = StorageValue<Aura, Vec<AuraId>, ValueQuery>;
+
+    #[storage_alias]
+    type BabeAuthorities = StorageValue<
+        Babe,
+        WeakBoundedVec<(BabeId, BabeAuthorityWeight), MaxAuthorities>,
+        ValueQuery,
+    >;
+
+    #[storage_alias]
+    type BabeNextAuthorities = StorageValue<
+        Babe,
+        WeakBoundedVec<(BabeId, BabeAuthorityWeight), MaxAuthorities>,
+        ValueQuery,
+    >;
+
+    /// The BABE key of a collator is its Aura key, both are sr25519 keys. Collators only have to
+    /// insert their existing key into the keystore under the BABE key type.
+    fn babe_id(aura: AuraId) -> BabeId {
+        sr25519::Public::from(aura).into()
+    }
+
+    #[cfg(feature = "try-runtime")]
+    #[storage_alias]
+    type OldNextKeys =
+        StorageMap<Session, frame_support::Twox64Concat, crate::AccountId, OldSessionKeys>;
+
+    /// Unversioned Aura to BABE migration, see [`MigrateAuraToBabe`].
+    pub struct UncheckedMigrateAuraToBabe;
+
+    impl OnRuntimeUpgrade for UncheckedMigrateAuraToBabe {
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            let aura_authorities = AuraAuthorities::get();
+            if !aura_authorities.is_empty() {
+                for account in pallet_session::Validators::<Runtime>::get() {
+                    frame_support::ensure!(
+                        OldNextKeys::get(&account).is_some(),
+                        "every validator has rotated in its GRANDPA key before the move to BABE"
+                    );
+                }
+            }
+
+            Ok(aura_authorities.encode())
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            let aura_authorities = AuraAuthorities::get();
+            if aura_authorities.is_empty() {
+                log::info!(target: LOG_TARGET, "No Aura authorities, nothing to migrate");
+                return <Runtime as frame_system::Config>::DbWeight::get().reads(1);
+            }
+
+            let authorities = aura_authorities
+                .into_iter()
+                .map(|aura| (babe_id(aura), 1))
+                .collect::<Vec<_>>();
+            let count = authorities.len() as u64;
+
+            let authorities = WeakBoundedVec::force_from(
+                authorities,
+                Some("Aura authorities exceed the BABE authority bound"),
+            );
+            BabeAuthorities::put(&authorities);
+            BabeNextAuthorities::put(&authorities);
+
+            // Keys of all validators are read and written back, and for each key the owner is
+            // updated.
+            pallet_session::Pallet::<Runtime>::upgrade_keys::<OldSessionKeys, _>(
+                |_account, old| SessionKeys {
+                    babe: babe_id(old.aura),
+                    grandpa: old.grandpa,
+                },
+            );
+            let validators =
+                pallet_session::Validators::<Runtime>::decode_len().unwrap_or(0) as u64;
+
+            log::info!(
+                target: LOG_TARGET,
+                "Migrated {} Aura authorities and the session keys of {} validators to BABE",
+                count,
+                validators,
+            );
+
+            <Runtime as frame_system::Config>::DbWeight::get()
+                .reads_writes(2 + 2 * validators, 2 + 5 * validators)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            let aura_authorities = Vec::<AuraId>::decode(&mut &state[..])
+                .map_err(|_| "the pre-upgrade state can't be decoded")?;
+            if aura_authorities.is_empty() {
+                return Ok(());
+            }
+
+            let babe_authorities = BabeAuthorities::get();
+
+            frame_support::ensure!(
+                babe_authorities.len() == aura_authorities.len(),
+                "every Aura authority is a BABE authority"
+            );
+            for (aura, (babe, _)) in aura_authorities.into_iter().zip(babe_authorities.iter()) {
+                frame_support::ensure!(
+                    babe_id(aura) == *babe,
+                    "BABE authorities keep the Aura keys"
+                );
+            }
+
+            for (account, keys) in pallet_session::QueuedKeys::<Runtime>::get() {
+                frame_support::ensure!(
+                    pallet_session::NextKeys::<Runtime>::get(&account) == Some(keys),
+                    "queued keys are the next keys of the validator"
+                );
+            }
+
+            Ok(())
+        }
+    }
+
+    /// [`UncheckedMigrateAuraToBabe`] guarded by the consensus storage version.
+    pub type MigrateAuraToBabe = VersionedMigration<
+        0,
+        1,
+        UncheckedMigrateAuraToBabe,
+        ConsensusStorageVersion,
+        <Runtime as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// Let BABE end the sessions, instead of ending them every six hours and changing epochs on their
+/// own with the same authorities forever.
+///
+/// Sessions now end with the BABE epoch and every new session enacts the next epoch with the
+/// session validators as authorities. Nodes already know the authorities of the next epoch from
+/// its announcement, so the validators the session hands over to at the first epoch change have to
+/// be exactly those. The migration queues them, taking the accounts from the session key owners.
+/// `pre_upgrade` fails if one of them has no owner, so check the upgrade with `try-runtime` before
+/// enacting it.
+///
+/// Session and epoch indices keep counting from where they are, so they differ on chains that
+/// rotated sessions by period before.
+pub mod v2 {
+    use super::*;
+    use frame_support::{
+        pallet_prelude::ValueQuery, storage_alias, traits::OnRuntimeUpgrade, weights::Weight,
+        Twox64Concat, WeakBoundedVec,
+    };
+    use sp_consensus_babe::BabeAuthorityWeight;
+    use sp_core::crypto::{ByteArray, KeyTypeId};
+    use sp_std::prelude::*;
+
+    #[cfg(feature = "try-runtime")]
+    use sp_runtime::TryRuntimeError;
+
+    const LOG_TARGET: &str = "runtime::migrations::v2";
+
+    #[storage_alias]
+    type BabeNextAuthorities = StorageValue<
+        Babe,
+        WeakBoundedVec<(BabeId, BabeAuthorityWeight), MaxAuthorities>,
+        ValueQuery,
+    >;
+
+    #[storage_alias]
+    type KeyOwner = StorageMap<Session, Twox64Concat, (KeyTypeId, Vec<u8>), crate::AccountId>;
+
+    #[storage_alias]
+    type QueuedChanged = StorageValue<Session, bool, ValueQuery>;
+
+    /// The next BABE authorities with their accounts and session keys, or the first authority
+    /// without a key owner.
+    fn next_authorities_with_keys() -> Result<Vec<(crate::AccountId, SessionKeys)>, BabeId> {
+        BabeNextAuthorities::get()
+            .into_iter()
+            .map(|(babe, _)| {
+                let account = KeyOwner::get((sp_consensus_babe::KEY_TYPE, babe.to_raw_vec()))
+                    .ok_or_else(|| babe.clone())?;
+                let keys = pallet_session::NextKeys::<Runtime>::get(&account)
+                    .ok_or_else(|| babe.clone())?;
+                // The key the epoch was announced with, even if the account rotated it since.
+                Ok((account, SessionKeys { babe, ..keys }))
+            })
+            .collect()
+    }
+
+    /// Unversioned session alignment, see [`MigrateSessionsToBabeEpochs`].
+    pub struct UncheckedMigrateSessionsToBabeEpochs;
+
+    impl OnRuntimeUpgrade for UncheckedMigrateSessionsToBabeEpochs {
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+            next_authorities_with_keys().map_err(|_| {
+                TryRuntimeError::Other("every next BABE authority has a session key owner")
+            })?;
+            Ok(Vec::new())
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            let authorities = BabeNextAuthorities::decode_len().unwrap_or(0) as u64;
+            let weight =
+                <Runtime as frame_system::Config>::DbWeight::get().reads(2 + 2 * authorities);
+
+            let queued = match next_authorities_with_keys() {
+                Ok(queued) => queued,
+                Err(babe) => {
+                    log::error!(
+                        target: LOG_TARGET,
+                        "Next BABE authority {:?} has no session key owner, not aligning sessions",
+                        babe,
+                    );
+                    return weight;
+                }
+            };
+
+            let already_queued = pallet_session::QueuedKeys::<Runtime>::get()
+                .iter()
+                .map(|(_, keys)| &keys.babe)
+                .eq(queued.iter().map(|(_, keys)| &keys.babe));
+            if already_queued {
+                log::info!(target: LOG_TARGET, "Sessions already hand over to the next epoch");
+                return weight
+                    .saturating_add(<Runtime as frame_system::Config>::DbWeight::get().reads(1));
+            }
+
+            log::info!(
+                target: LOG_TARGET,
+                "Queued the {} next BABE authorities for the next session",
+                queued.len(),
+            );
+            pallet_session::QueuedKeys::<Runtime>::put(queued);
+            QueuedChanged::put(true);
+
+            weight.saturating_add(
+                <Runtime as frame_system::Config>::DbWeight::get().reads_writes(1, 2),
+            )
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+            frame_support::ensure!(
+                pallet_session::QueuedKeys::<Runtime>::get()
+                    .iter()
+                    .map(|(_, keys)| &keys.babe)
+                    .eq(BabeNextAuthorities::get().iter().map(|(babe, _)| babe)),
+                "the next session hands over to the next BABE authorities"
+            );
+            Ok(())
+        }
+    }
+
+    /// [`UncheckedMigrateSessionsToBabeEpochs`] guarded by the consensus storage version.
+    pub type MigrateSessionsToBabeEpochs = VersionedMigration<
+        1,
+        2,
+        UncheckedMigrateSessionsToBabeEpochs,
+        ConsensusStorageVersion,
+        <Runtime as frame_system::Config>::DbWeight,
+    >;
+}
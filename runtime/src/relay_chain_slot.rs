@@ -0,0 +1,66 @@
+//! The slot duration of the relay chain the parachain is attached to.
+
+pub use pallet_relay_chain_slot::*;
+
+// The pallet macro clones `PhantomData` in the code it generates.
+#[allow(clippy::clone_on_copy)]
+#[frame_support::pallet]
+pub mod pallet_relay_chain_slot {
+    use frame_support::pallet_prelude::*;
+
+    /// The slot duration of production relay chains, in milliseconds.
+    pub const DEFAULT_SLOT_DURATION_MILLIS: u32 = 6000;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {}
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::type_value]
+    pub fn DefaultSlotDurationMillis() -> u32 {
+        DEFAULT_SLOT_DURATION_MILLIS
+    }
+
+    /// Relay chain slot duration, in milliseconds.
+    ///
+    /// Set by the chain spec. Chains launched before it was a genesis field use the default.
+    #[pallet::storage]
+    pub type SlotDurationMillis<T: Config> =
+        StorageValue<_, u32, ValueQuery, DefaultSlotDurationMillis>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        /// Relay chain slot duration, in milliseconds.
+        pub slot_duration_millis: u32,
+        #[serde(skip)]
+        pub _config: PhantomData<T>,
+    }
+
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
+            Self {
+                slot_duration_millis: DEFAULT_SLOT_DURATION_MILLIS,
+                _config: PhantomData,
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            assert!(
+                self.slot_duration_millis > 0,
+                "the relay chain slot duration must not be zero"
+            );
+            SlotDurationMillis::<T>::put(self.slot_duration_millis);
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Relay chain slot duration, in milliseconds.
+        pub fn slot_duration_millis() -> u32 {
+            SlotDurationMillis::<T>::get()
+        }
+    }
+}
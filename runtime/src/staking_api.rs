@@ -0,0 +1,67 @@
+//! Runtime API for staking front-ends.
+//!
+//! Until the runtime has `pallet_staking`, the validators are the session validators picked by
+//! collator selection. [`StakingApi::validators`] returns them along with their identity display
+//! names, so a validator list is rendered with a single call.
+
+use crate::{AccountId, Identity, Session};
+use codec::{Codec, Decode, Encode};
+use pallet_identity::Data;
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// A validator of the current session.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ValidatorInfo<AccountId> {
+    /// The validator's account.
+    pub account: AccountId,
+    /// The display name of the on-chain identity, if one is set and stored in raw form.
+    pub display: Option<Vec<u8>>,
+}
+
+sp_api::decl_runtime_apis! {
+    /// Validator information for staking front-ends.
+    pub trait StakingApi<AccountId> where AccountId: Codec {
+        /// The validators of the current session.
+        fn validators() -> Vec<ValidatorInfo<AccountId>>;
+    }
+}
+
+/// The raw bytes of `data`, if it is stored in raw form.
+fn raw(data: Data) -> Option<Vec<u8>> {
+    match data {
+        Data::Raw(name) => Some(name.into_inner()),
+        _ => None,
+    }
+}
+
+/// The display name `who` set in their identity.
+///
+/// A sub-account without its own identity shows as `parent/sub`, the display name of its parent
+/// identity followed by the name it was given as a sub.
+pub fn display_name(who: &AccountId) -> Option<Vec<u8>> {
+    if let Some((registration, _username)) = Identity::identity(who) {
+        return raw(registration.info.display);
+    }
+
+    let (parent, sub_name) = Identity::super_of(who)?;
+    let (registration, _username) = Identity::identity(&parent)?;
+    let mut name = raw(registration.info.display)?;
+    if let Some(sub_name) = raw(sub_name) {
+        name.push(b'/');
+        name.extend(sub_name);
+    }
+    Some(name)
+}
+
+/// The validators of the current session, with their display names.
+pub fn validators() -> Vec<ValidatorInfo<AccountId>> {
+    Session::validators()
+        .into_iter()
+        .map(|account| ValidatorInfo {
+            display: display_name(&account),
+            account,
+        })
+        .collect()
+}
@@ -0,0 +1,39 @@
+//! PLACEHOLDER weights for `cumulus_pallet_parachain_system`, not benchmark output.
+//!
+//! The values are hand-written estimates based on the reference weights upstream ships for the
+//! pallet, rounded up. They have not been measured on any machine.
+//! They must be replaced with the output of `scripts/benchmark-weights.sh` on our reference
+//! machine before a release. The script calls:
+//!
+//! ```text
+//! ./target/release/parachain-template-node
+//!     benchmark
+//!     pallet
+//!     --chain=dev
+//!     --wasm-execution=compiled
+//!     --pallet=cumulus_pallet_parachain_system
+//!     --extrinsic=*
+//!     --steps=50
+//!     --repeat=20
+//!     --output=./runtime/src/weights/cumulus_pallet_parachain_system.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `cumulus_pallet_parachain_system`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> cumulus_pallet_parachain_system::WeightInfo for WeightInfo<T> {
+	fn enqueue_inbound_downward_messages(n: u32) -> Weight {
+		Weight::from_parts(2_500_000, 12000)
+			.saturating_add(Weight::from_parts(25_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+}
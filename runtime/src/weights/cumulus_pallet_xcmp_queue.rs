@@ -0,0 +1,71 @@
+//! PLACEHOLDER weights for `cumulus_pallet_xcmp_queue`, not benchmark output.
+//!
+//! The values are hand-written estimates based on the reference weights upstream ships for the
+//! pallet, rounded up. They have not been measured on any machine.
+//! They must be replaced with the output of `scripts/benchmark-weights.sh` on our reference
+//! machine before a release. The script calls:
+//!
+//! ```text
+//! ./target/release/parachain-template-node
+//!     benchmark
+//!     pallet
+//!     --chain=dev
+//!     --wasm-execution=compiled
+//!     --pallet=cumulus_pallet_xcmp_queue
+//!     --extrinsic=*
+//!     --steps=50
+//!     --repeat=20
+//!     --output=./runtime/src/weights/cumulus_pallet_xcmp_queue.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `cumulus_pallet_xcmp_queue`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> cumulus_pallet_xcmp_queue::WeightInfo for WeightInfo<T> {
+	fn set_config_with_u32() -> Weight {
+		Weight::from_parts(6_000_000, 1561)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn enqueue_xcmp_message() -> Weight {
+		Weight::from_parts(20_000_000, 3517)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+
+	fn suspend_channel() -> Weight {
+		Weight::from_parts(4_000_000, 1561)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn resume_channel() -> Weight {
+		Weight::from_parts(5_000_000, 1596)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn take_first_concatenated_xcm() -> Weight {
+		Weight::from_parts(7_000_000, 0)
+	}
+
+	fn on_idle_good_msg() -> Weight {
+		Weight::from_parts(110_000_000, 69050)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+
+	fn on_idle_large_msg() -> Weight {
+		Weight::from_parts(65_000_000, 69050)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+}
@@ -18,10 +18,38 @@
 //! Expose the auto generated weight files.
 
 pub mod block_weights;
+pub mod cumulus_pallet_parachain_system;
+pub mod cumulus_pallet_xcmp_queue;
 pub mod extrinsic_weights;
+pub mod pallet_babe;
+pub mod pallet_collator_selection;
+pub mod pallet_grandpa;
+pub mod pallet_message_queue;
+pub mod pallet_session;
+pub mod pallet_timestamp;
+pub mod pallet_xcm;
 pub mod paritydb_weights;
 pub mod rocksdb_weights;
+pub mod xcm;
 
 pub use block_weights::constants::BlockExecutionWeight;
 pub use extrinsic_weights::constants::ExtrinsicBaseWeight;
-pub use rocksdb_weights::constants::RocksDbWeight;
+#[cfg(feature = "paritydb-weights")]
+pub use paritydb_weights::constants::ParityDbWeight as DbWeight;
+#[cfg(not(feature = "paritydb-weights"))]
+pub use rocksdb_weights::constants::RocksDbWeight as DbWeight;
+
+/// The database backend [`DbWeight`] is for, as named by `--database`.
+#[cfg(feature = "paritydb-weights")]
+pub const DB_WEIGHT_BACKEND: &str = "paritydb";
+/// The database backend [`DbWeight`] is for, as named by `--database`.
+#[cfg(not(feature = "paritydb-weights"))]
+pub const DB_WEIGHT_BACKEND: &str = "rocksdb";
+
+sp_api::decl_runtime_apis! {
+    /// The database the runtime weights are for.
+    pub trait DbWeightApi {
+        /// [`DB_WEIGHT_BACKEND`] of the runtime, so nodes can compare it with their database.
+        fn db_weight_backend() -> sp_std::vec::Vec<u8>;
+    }
+}
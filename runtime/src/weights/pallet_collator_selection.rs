@@ -0,0 +1,122 @@
+//! PLACEHOLDER weights for `pallet_collator_selection`, not benchmark output.
+//!
+//! The values are hand-written estimates based on the reference weights upstream ships for the
+//! pallet, rounded up. They have not been measured on any machine.
+//! They must be replaced with the output of `scripts/benchmark-weights.sh` on our reference
+//! machine before a release. The script calls:
+//!
+//! ```text
+//! ./target/release/parachain-template-node
+//!     benchmark
+//!     pallet
+//!     --chain=dev
+//!     --wasm-execution=compiled
+//!     --pallet=pallet_collator_selection
+//!     --extrinsic=*
+//!     --steps=50
+//!     --repeat=20
+//!     --output=./runtime/src/weights/pallet_collator_selection.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_collator_selection`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_collator_selection::WeightInfo for WeightInfo<T> {
+	fn set_invulnerables(b: u32) -> Weight {
+		Weight::from_parts(12_000_000, 1806)
+			.saturating_add(Weight::from_parts(4_000_000, 0).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(b.into())))
+			.saturating_add(Weight::from_parts(0, 2554).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn add_invulnerable(b: u32, c: u32) -> Weight {
+		Weight::from_parts(48_000_000, 6287)
+			.saturating_add(Weight::from_parts(65_000, 0).saturating_mul(b.into()))
+			.saturating_add(Weight::from_parts(55_000, 0).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 37).saturating_mul(b.into()))
+			.saturating_add(Weight::from_parts(0, 53).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+
+	fn remove_invulnerable(b: u32) -> Weight {
+		Weight::from_parts(12_500_000, 6287)
+			.saturating_add(Weight::from_parts(60_000, 0).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn set_desired_candidates() -> Weight {
+		Weight::from_parts(5_500_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn set_candidacy_bond(c: u32, k: u32) -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(Weight::from_parts(90_000, 0).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(90_000, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 2602).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 2602).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn register_as_candidate(c: u32) -> Weight {
+		Weight::from_parts(42_000_000, 6287)
+			.saturating_add(Weight::from_parts(200_000, 0).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 54).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+
+	fn leave_intent(c: u32) -> Weight {
+		Weight::from_parts(30_000_000, 6287)
+			.saturating_add(Weight::from_parts(110_000, 0).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 49).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn update_bond(c: u32) -> Weight {
+		Weight::from_parts(45_000_000, 6287)
+			.saturating_add(Weight::from_parts(160_000, 0).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 49).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn take_candidate_slot(c: u32) -> Weight {
+		Weight::from_parts(62_000_000, 6287)
+			.saturating_add(Weight::from_parts(230_000, 0).saturating_mul(c.into()))
+			.saturating_add(Weight::from_parts(0, 54).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+
+	fn note_author() -> Weight {
+		Weight::from_parts(42_000_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+
+	fn new_session(r: u32, c: u32) -> Weight {
+		Weight::from_parts(22_000_000, 6287)
+			.saturating_add(Weight::from_parts(10_000, 0).saturating_mul(r.into()))
+			.saturating_add(Weight::from_parts(15_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
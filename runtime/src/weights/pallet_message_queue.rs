@@ -0,0 +1,89 @@
+//! PLACEHOLDER weights for `pallet_message_queue`, not benchmark output.
+//!
+//! The values are hand-written estimates based on the reference weights upstream ships for the
+//! pallet, rounded up. They have not been measured on any machine.
+//! They must be replaced with the output of `scripts/benchmark-weights.sh` on our reference
+//! machine before a release. The script calls:
+//!
+//! ```text
+//! ./target/release/parachain-template-node
+//!     benchmark
+//!     pallet
+//!     --chain=dev
+//!     --wasm-execution=compiled
+//!     --pallet=pallet_message_queue
+//!     --extrinsic=*
+//!     --steps=50
+//!     --repeat=20
+//!     --output=./runtime/src/weights/pallet_message_queue.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_message_queue`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_message_queue::WeightInfo for WeightInfo<T> {
+	fn ready_ring_knit() -> Weight {
+		Weight::from_parts(12_000_000, 6212)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn ready_ring_unknit() -> Weight {
+		Weight::from_parts(11_500_000, 6212)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn service_queue_base() -> Weight {
+		Weight::from_parts(4_500_000, 3517)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn service_page_base_completion() -> Weight {
+		Weight::from_parts(6_000_000, 69050)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn service_page_base_no_completion() -> Weight {
+		Weight::from_parts(6_500_000, 69050)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn service_page_item() -> Weight {
+		Weight::from_parts(170_000_000, 0)
+	}
+
+	fn bump_service_head() -> Weight {
+		Weight::from_parts(7_000_000, 3517)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn reap_page() -> Weight {
+		Weight::from_parts(54_000_000, 69050)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn execute_overweight_page_removed() -> Weight {
+		Weight::from_parts(70_000_000, 69050)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn execute_overweight_page_updated() -> Weight {
+		Weight::from_parts(80_000_000, 69050)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
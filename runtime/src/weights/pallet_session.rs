@@ -0,0 +1,43 @@
+//! PLACEHOLDER weights for `pallet_session`, not benchmark output.
+//!
+//! The values are hand-written estimates based on the reference weights upstream ships for the
+//! pallet, rounded up. They have not been measured on any machine.
+//! They must be replaced with the output of `scripts/benchmark-weights.sh` on our reference
+//! machine before a release. The script calls:
+//!
+//! ```text
+//! ./target/release/parachain-template-node
+//!     benchmark
+//!     pallet
+//!     --chain=dev
+//!     --wasm-execution=compiled
+//!     --pallet=pallet_session
+//!     --extrinsic=*
+//!     --steps=50
+//!     --repeat=20
+//!     --output=./runtime/src/weights/pallet_session.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_session`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_session::WeightInfo for WeightInfo<T> {
+	fn set_keys() -> Weight {
+		Weight::from_parts(26_000_000, 6120)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+
+	fn purge_keys() -> Weight {
+		Weight::from_parts(18_500_000, 3700)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+}
@@ -0,0 +1,144 @@
+//! PLACEHOLDER weights for `pallet_xcm`, not benchmark output.
+//!
+//! The values are hand-written estimates based on the reference weights upstream ships for the
+//! pallet, rounded up. They have not been measured on any machine.
+//! They must be replaced with the output of `scripts/benchmark-weights.sh` on our reference
+//! machine before a release. The script calls:
+//!
+//! ```text
+//! ./target/release/parachain-template-node
+//!     benchmark
+//!     pallet
+//!     --chain=dev
+//!     --wasm-execution=compiled
+//!     --pallet=pallet_xcm
+//!     --extrinsic=*
+//!     --steps=50
+//!     --repeat=20
+//!     --output=./runtime/src/weights/pallet_xcm.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_xcm`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_xcm::WeightInfo for WeightInfo<T> {
+	fn send() -> Weight {
+		Weight::from_parts(30_000_000, 3610)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn teleport_assets() -> Weight {
+		Weight::from_parts(105_000_000, 3610)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn reserve_transfer_assets() -> Weight {
+		Weight::from_parts(105_000_000, 3610)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn transfer_assets() -> Weight {
+		Weight::from_parts(105_000_000, 3610)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn execute() -> Weight {
+		Weight::from_parts(18_446_744_073_709_551_000, 0)
+	}
+
+	fn force_xcm_version() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn force_default_xcm_version() -> Weight {
+		Weight::from_parts(2_500_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn force_subscribe_version_notify() -> Weight {
+		Weight::from_parts(35_000_000, 3610)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+
+	fn force_unsubscribe_version_notify() -> Weight {
+		Weight::from_parts(37_000_000, 3827)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+
+	fn force_suspension() -> Weight {
+		Weight::from_parts(2_500_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn migrate_supported_version() -> Weight {
+		Weight::from_parts(20_000_000, 13387)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn migrate_version_notifiers() -> Weight {
+		Weight::from_parts(20_000_000, 13391)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn already_notified_target() -> Weight {
+		Weight::from_parts(21_000_000, 15880)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+	}
+
+	fn notify_current_targets() -> Weight {
+		Weight::from_parts(40_000_000, 6082)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn notify_target_migration_fail() -> Weight {
+		Weight::from_parts(12_000_000, 11068)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+	}
+
+	fn migrate_version_notify_targets() -> Weight {
+		Weight::from_parts(20_000_000, 13405)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	fn migrate_and_notify_old_targets() -> Weight {
+		Weight::from_parts(48_000_000, 13471)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+
+	fn new_query() -> Weight {
+		Weight::from_parts(4_000_000, 1485)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn take_response() -> Weight {
+		Weight::from_parts(25_000_000, 7576)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	fn claim_assets() -> Weight {
+		Weight::from_parts(40_000_000, 3488)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
@@ -15,6 +15,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! PLACEHOLDER: Substrate's default `RocksDB` weights, unchanged and not measured for this chain.
+//!
+//! They must be replaced with the output of the storage benchmark in
+//! `scripts/benchmark-weights.sh`, run on our reference machine against a synced node's database,
+//! before a release.
+
 pub mod constants {
     use frame_support::{
         parameter_types,
@@ -0,0 +1,229 @@
+//! Weights of XCM instructions, from the `pallet_xcm_benchmarks` weight files. Those are
+//! placeholders until `scripts/benchmark-weights.sh` has been run on our reference machine.
+//!
+//! Instructions that move assets are weighed per asset, with the weight of the relay chain token
+//! in `ForeignAssets`. Sibling assets take the same path, and the native token in `Balances` costs
+//! less, so no asset is charged below its cost. Instructions the executor is not configured for
+//! weigh [`Weight::MAX`], so messages using them are never executed.
+
+mod pallet_xcm_benchmarks_fungible;
+mod pallet_xcm_benchmarks_generic;
+
+use crate::Runtime;
+use frame_support::weights::Weight;
+use pallet_xcm_benchmarks_fungible::WeightInfo as XcmFungibleWeight;
+use pallet_xcm_benchmarks_generic::WeightInfo as XcmGeneric;
+use sp_std::prelude::*;
+use xcm::{latest::prelude::*, DoubleEncoded};
+
+/// Wildcards are weighed as if they matched this many assets.
+const MAX_ASSETS: u64 = 100;
+
+trait WeighAssets {
+    fn weigh_assets(&self, weight: Weight) -> Weight;
+}
+
+impl WeighAssets for AssetFilter {
+    fn weigh_assets(&self, weight: Weight) -> Weight {
+        match self {
+            Self::Definite(assets) => weight.saturating_mul(assets.inner().len() as u64),
+            Self::Wild(asset) => match asset {
+                All => weight.saturating_mul(MAX_ASSETS),
+                AllOf { fun, .. } => match fun {
+                    WildFungibility::Fungible => weight,
+                    WildFungibility::NonFungible => weight.saturating_mul(MAX_ASSETS),
+                },
+                AllCounted(count) | AllOfCounted { count, .. } => {
+                    weight.saturating_mul(MAX_ASSETS.min(*count as u64))
+                }
+            },
+        }
+    }
+}
+
+impl WeighAssets for Assets {
+    fn weigh_assets(&self, weight: Weight) -> Weight {
+        weight.saturating_mul(self.inner().len() as u64)
+    }
+}
+
+/// The weight of each XCM instruction, from the `pallet_xcm_benchmarks` weight files.
+///
+/// `Transact` weighs the instruction alone, `WeightInfoBounds` adds the weight the message
+/// declares for the call.
+pub struct XcmWeight<Call>(core::marker::PhantomData<Call>);
+impl<Call> XcmWeightInfo<Call> for XcmWeight<Call> {
+    fn withdraw_asset(assets: &Assets) -> Weight {
+        assets.weigh_assets(XcmFungibleWeight::<Runtime>::withdraw_asset())
+    }
+    fn reserve_asset_deposited(assets: &Assets) -> Weight {
+        assets.weigh_assets(XcmFungibleWeight::<Runtime>::reserve_asset_deposited())
+    }
+    fn receive_teleported_asset(assets: &Assets) -> Weight {
+        assets.weigh_assets(XcmFungibleWeight::<Runtime>::receive_teleported_asset())
+    }
+    fn query_response(
+        _query_id: &u64,
+        _response: &Response,
+        _max_weight: &Weight,
+        _querier: &Option<Location>,
+    ) -> Weight {
+        XcmGeneric::<Runtime>::query_response()
+    }
+    fn transfer_asset(assets: &Assets, _dest: &Location) -> Weight {
+        assets.weigh_assets(XcmFungibleWeight::<Runtime>::transfer_asset())
+    }
+    fn transfer_reserve_asset(assets: &Assets, _dest: &Location, _xcm: &Xcm<()>) -> Weight {
+        assets.weigh_assets(XcmFungibleWeight::<Runtime>::transfer_reserve_asset())
+    }
+    fn transact(
+        _origin_kind: &OriginKind,
+        _require_weight_at_most: &Weight,
+        _call: &DoubleEncoded<Call>,
+    ) -> Weight {
+        XcmGeneric::<Runtime>::transact()
+    }
+    fn hrmp_new_channel_open_request(
+        _sender: &u32,
+        _max_message_size: &u32,
+        _max_capacity: &u32,
+    ) -> Weight {
+        // Only the relay chain executes HRMP channel instructions.
+        Weight::MAX
+    }
+    fn hrmp_channel_accepted(_recipient: &u32) -> Weight {
+        Weight::MAX
+    }
+    fn hrmp_channel_closing(_initiator: &u32, _sender: &u32, _recipient: &u32) -> Weight {
+        Weight::MAX
+    }
+    fn clear_origin() -> Weight {
+        XcmGeneric::<Runtime>::clear_origin()
+    }
+    fn descend_origin(_who: &InteriorLocation) -> Weight {
+        XcmGeneric::<Runtime>::descend_origin()
+    }
+    fn report_error(_query_response_info: &QueryResponseInfo) -> Weight {
+        XcmGeneric::<Runtime>::report_error()
+    }
+    fn deposit_asset(assets: &AssetFilter, _dest: &Location) -> Weight {
+        assets.weigh_assets(XcmFungibleWeight::<Runtime>::deposit_asset())
+    }
+    fn deposit_reserve_asset(assets: &AssetFilter, _dest: &Location, _xcm: &Xcm<()>) -> Weight {
+        assets.weigh_assets(XcmFungibleWeight::<Runtime>::deposit_reserve_asset())
+    }
+    fn exchange_asset(_give: &AssetFilter, _receive: &Assets, _maximal: &bool) -> Weight {
+        // There is no `AssetExchanger`.
+        Weight::MAX
+    }
+    fn initiate_reserve_withdraw(
+        assets: &AssetFilter,
+        _reserve: &Location,
+        _xcm: &Xcm<()>,
+    ) -> Weight {
+        assets.weigh_assets(XcmFungibleWeight::<Runtime>::initiate_reserve_withdraw())
+    }
+    fn initiate_teleport(assets: &AssetFilter, _dest: &Location, _xcm: &Xcm<()>) -> Weight {
+        assets.weigh_assets(XcmFungibleWeight::<Runtime>::initiate_teleport())
+    }
+    fn report_holding(_response_info: &QueryResponseInfo, _assets: &AssetFilter) -> Weight {
+        XcmGeneric::<Runtime>::report_holding()
+    }
+    fn buy_execution(_fees: &Asset, _weight_limit: &WeightLimit) -> Weight {
+        XcmGeneric::<Runtime>::buy_execution()
+    }
+    fn refund_surplus() -> Weight {
+        XcmGeneric::<Runtime>::refund_surplus()
+    }
+    fn set_error_handler(_xcm: &Xcm<Call>) -> Weight {
+        XcmGeneric::<Runtime>::set_error_handler()
+    }
+    fn set_appendix(_xcm: &Xcm<Call>) -> Weight {
+        XcmGeneric::<Runtime>::set_appendix()
+    }
+    fn clear_error() -> Weight {
+        XcmGeneric::<Runtime>::clear_error()
+    }
+    fn claim_asset(_assets: &Assets, _ticket: &Location) -> Weight {
+        XcmGeneric::<Runtime>::claim_asset()
+    }
+    fn trap(_code: &u64) -> Weight {
+        XcmGeneric::<Runtime>::trap()
+    }
+    fn subscribe_version(_query_id: &QueryId, _max_response_weight: &Weight) -> Weight {
+        XcmGeneric::<Runtime>::subscribe_version()
+    }
+    fn unsubscribe_version() -> Weight {
+        XcmGeneric::<Runtime>::unsubscribe_version()
+    }
+    fn burn_asset(assets: &Assets) -> Weight {
+        assets.weigh_assets(XcmGeneric::<Runtime>::burn_asset())
+    }
+    fn expect_asset(assets: &Assets) -> Weight {
+        assets.weigh_assets(XcmGeneric::<Runtime>::expect_asset())
+    }
+    fn expect_origin(_origin: &Option<Location>) -> Weight {
+        XcmGeneric::<Runtime>::expect_origin()
+    }
+    fn expect_error(_error: &Option<(u32, XcmError)>) -> Weight {
+        XcmGeneric::<Runtime>::expect_error()
+    }
+    fn expect_transact_status(_transact_status: &MaybeErrorCode) -> Weight {
+        XcmGeneric::<Runtime>::expect_transact_status()
+    }
+    fn query_pallet(_module_name: &Vec<u8>, _response_info: &QueryResponseInfo) -> Weight {
+        XcmGeneric::<Runtime>::query_pallet()
+    }
+    fn expect_pallet(
+        _index: &u32,
+        _name: &Vec<u8>,
+        _module_name: &Vec<u8>,
+        _crate_major: &u32,
+        _min_crate_minor: &u32,
+    ) -> Weight {
+        XcmGeneric::<Runtime>::expect_pallet()
+    }
+    fn report_transact_status(_response_info: &QueryResponseInfo) -> Weight {
+        XcmGeneric::<Runtime>::report_transact_status()
+    }
+    fn clear_transact_status() -> Weight {
+        XcmGeneric::<Runtime>::clear_transact_status()
+    }
+    fn universal_origin(_: &Junction) -> Weight {
+        // There are no `UniversalAliases`.
+        Weight::MAX
+    }
+    fn export_message(_: &NetworkId, _: &Junctions, _: &Xcm<()>) -> Weight {
+        // There is no `MessageExporter`.
+        Weight::MAX
+    }
+    fn lock_asset(_: &Asset, _: &Location) -> Weight {
+        // There is no `AssetLocker`.
+        Weight::MAX
+    }
+    fn unlock_asset(_: &Asset, _: &Location) -> Weight {
+        Weight::MAX
+    }
+    fn note_unlockable(_: &Asset, _: &Location) -> Weight {
+        Weight::MAX
+    }
+    fn request_unlock(_: &Asset, _: &Location) -> Weight {
+        Weight::MAX
+    }
+    fn set_fees_mode(_: &bool) -> Weight {
+        XcmGeneric::<Runtime>::set_fees_mode()
+    }
+    fn set_topic(_topic: &[u8; 32]) -> Weight {
+        XcmGeneric::<Runtime>::set_topic()
+    }
+    fn clear_topic() -> Weight {
+        XcmGeneric::<Runtime>::clear_topic()
+    }
+    fn alias_origin(_: &Location) -> Weight {
+        // There are no `Aliasers`.
+        Weight::MAX
+    }
+    fn unpaid_execution(_: &WeightLimit, _: &Option<Location>) -> Weight {
+        XcmGeneric::<Runtime>::unpaid_execution()
+    }
+}
@@ -0,0 +1,86 @@
+//! PLACEHOLDER weights for `pallet_xcm_benchmarks::fungible`, not benchmark output.
+//!
+//! The values are hand-written estimates based on the reference weights upstream ships for
+//! `pallet_assets` and the pallet, rounded up. They have not been measured on any machine. They
+//! are meant for the relay chain token in `ForeignAssets`, with teleports tracked in the checking
+//! account, which is what the benchmarks move.
+//! They must be replaced with the output of `scripts/benchmark-weights.sh` on our reference
+//! machine before a release. The script calls:
+//!
+//! ```text
+//! ./target/release/parachain-template-node
+//!     benchmark
+//!     pallet
+//!     --chain=dev
+//!     --wasm-execution=compiled
+//!     --pallet=pallet_xcm_benchmarks::fungible
+//!     --extrinsic=*
+//!     --steps=50
+//!     --repeat=20
+//!     --template=./scripts/xcm-bench-template.hbs
+//!     --output=./runtime/src/weights/xcm/pallet_xcm_benchmarks_fungible.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_xcm_benchmarks::fungible`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo<T> {
+	pub fn withdraw_asset() -> Weight {
+		Weight::from_parts(40_000_000, 4273)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	pub fn transfer_asset() -> Weight {
+		Weight::from_parts(65_000_000, 7404)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+
+	pub fn transfer_reserve_asset() -> Weight {
+		Weight::from_parts(120_000_000, 7404)
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+
+	pub fn reserve_asset_deposited() -> Weight {
+		Weight::from_parts(6_000_000, 0)
+	}
+
+	pub fn initiate_reserve_withdraw() -> Weight {
+		Weight::from_parts(85_000_000, 4273)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+
+	pub fn receive_teleported_asset() -> Weight {
+		Weight::from_parts(35_000_000, 4273)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+
+	pub fn deposit_asset() -> Weight {
+		Weight::from_parts(40_000_000, 4273)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+
+	pub fn deposit_reserve_asset() -> Weight {
+		Weight::from_parts(75_000_000, 4273)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+
+	pub fn initiate_teleport() -> Weight {
+		Weight::from_parts(80_000_000, 4273)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+}
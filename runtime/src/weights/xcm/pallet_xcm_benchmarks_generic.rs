@@ -0,0 +1,159 @@
+//! PLACEHOLDER weights for `pallet_xcm_benchmarks::generic`, not benchmark output.
+//!
+//! The values are hand-written estimates based on the reference weights upstream ships for the
+//! pallet, rounded up. They have not been measured on any machine.
+//! They must be replaced with the output of `scripts/benchmark-weights.sh` on our reference
+//! machine before a release. The script calls:
+//!
+//! ```text
+//! ./target/release/parachain-template-node
+//!     benchmark
+//!     pallet
+//!     --chain=dev
+//!     --wasm-execution=compiled
+//!     --pallet=pallet_xcm_benchmarks::generic
+//!     --extrinsic=*
+//!     --steps=50
+//!     --repeat=20
+//!     --template=./scripts/xcm-bench-template.hbs
+//!     --output=./runtime/src/weights/xcm/pallet_xcm_benchmarks_generic.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_xcm_benchmarks::generic`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo<T> {
+	pub fn report_holding() -> Weight {
+		Weight::from_parts(60_000_000, 3610)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	pub fn buy_execution() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn query_response() -> Weight {
+		Weight::from_parts(7_000_000, 3568)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	pub fn transact() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+	}
+
+	pub fn refund_surplus() -> Weight {
+		Weight::from_parts(1_500_000, 0)
+	}
+
+	pub fn set_error_handler() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn set_appendix() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn clear_error() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn descend_origin() -> Weight {
+		Weight::from_parts(1_500_000, 0)
+	}
+
+	pub fn clear_origin() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn report_error() -> Weight {
+		Weight::from_parts(55_000_000, 3610)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	pub fn claim_asset() -> Weight {
+		Weight::from_parts(10_000_000, 3568)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	pub fn trap() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn subscribe_version() -> Weight {
+		Weight::from_parts(60_000_000, 3610)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+
+	pub fn unsubscribe_version() -> Weight {
+		Weight::from_parts(4_500_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	pub fn burn_asset() -> Weight {
+		Weight::from_parts(1_500_000, 0)
+	}
+
+	pub fn expect_asset() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn expect_origin() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn expect_error() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn expect_transact_status() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn query_pallet() -> Weight {
+		Weight::from_parts(60_000_000, 3610)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	pub fn expect_pallet() -> Weight {
+		Weight::from_parts(5_000_000, 0)
+	}
+
+	pub fn report_transact_status() -> Weight {
+		Weight::from_parts(55_000_000, 3610)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+
+	pub fn clear_transact_status() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn set_topic() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn clear_topic() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn set_fees_mode() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	pub fn unpaid_execution() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+}
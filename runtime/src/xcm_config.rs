@@ -1,29 +1,48 @@
 use super::{
-    AccountId, AllPalletsWithSystem, Balances, ParachainInfo, ParachainSystem, PolkadotXcm,
-    Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, WeightToFee, XcmpQueue,
+    governance::GeneralAdminOrRoot, AccountId, AllPalletsWithSystem, Balance, Balances,
+    DealWithFees, ForeignAssets, NativeToForeignAssetBalance, ParachainInfo, ParachainSystem,
+    PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, Treasury, WeightToFee,
+    XcmpQueue,
+};
+use cumulus_primitives_utility::{
+    ChargeWeightInFungibles, TakeFirstAssetTrader, XcmFeesTo32ByteAccount,
 };
 use frame_support::{
     parameter_types,
-    traits::{ConstU32, Contains, Everything, Nothing},
-    weights::Weight,
+    traits::{
+        tokens::ConversionToAssetBalance, ConstU32, Contains, ContainsPair, Equals, Everything,
+        Nothing, ProcessMessageError,
+    },
+    weights::{Weight, WeightToFee as _},
 };
-use frame_system::EnsureRoot;
 use pallet_xcm::XcmPassthrough;
+#[cfg(feature = "relay-teleports")]
+use parachains_common::xcm_config::ConcreteAssetFromSystem;
 use polkadot_parachain_primitives::primitives::Sibling;
-use polkadot_runtime_common::impls::ToAuthor;
+use sp_runtime::traits::TryConvertInto;
 use xcm::latest::prelude::*;
+#[cfg(not(feature = "relay-teleports"))]
+use xcm_builder::Case;
 use xcm_builder::{
     AccountId32Aliases, AllowExplicitUnpaidExecutionFrom, AllowTopLevelPaidExecutionFrom,
-    DenyReserveTransferToRelayChain, DenyThenTry, EnsureXcmOrigin, FixedWeightBounds,
-    FrameTransactionalProcessor, FungibleAdapter, IsConcrete, NativeAsset, ParentIsPreset,
-    RelayChainAsNative, SiblingParachainAsNative, SiblingParachainConvertsVia,
+    DenyReserveTransferToRelayChain, DenyThenTry, DescribeAllTerminal, DescribeFamily,
+    EnsureXcmOrigin, FrameTransactionalProcessor, FungibleAdapter, FungiblesAdapter,
+    HashedDescription, IsConcrete, MatchedConvertedConcreteId, NoChecking, NonLocalMint,
+    ParentIsPreset, RelayChainAsNative, SiblingParachainAsNative, SiblingParachainConvertsVia,
     SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation, TakeWeightCredit,
-    TrailingSetTopicAsId, UsingComponents, WithComputedOrigin, WithUniqueTopic,
+    TrailingSetTopicAsId, UsingComponents, V4V3LocationConverter, WeightInfoBounds,
+    WithComputedOrigin, WithUniqueTopic,
+};
+use xcm_executor::{
+    traits::{Properties, ShouldExecute},
+    XcmExecutor,
 };
-use xcm_executor::XcmExecutor;
 
 parameter_types! {
     pub const RelayLocation: Location = Location::parent();
+    pub const HereLocation: Location = Location::here();
+    /// The id of the relay chain token in `ForeignAssets`.
+    pub RelayTokenId: xcm::v3::Location = xcm::v3::Location::parent();
     pub const RelayNetwork: Option<NetworkId> = None;
     pub RelayChainOrigin: RuntimeOrigin = cumulus_pallet_xcm::Origin::Relay.into();
     pub UniversalLocation: InteriorLocation = Parachain(ParachainInfo::parachain_id().into()).into();
@@ -39,14 +58,17 @@ pub type LocationToAccountId = (
     SiblingParachainConvertsVia<Sibling, AccountId>,
     // Straight up local `AccountId32` origins just alias directly to `AccountId`.
     AccountId32Aliases<RelayNetwork, AccountId>,
+    // Accounts on the relay chain and on siblings get an account of their own, derived from a
+    // hash of their location, so they can `Transact` without keys on this chain.
+    HashedDescription<AccountId, DescribeFamily<DescribeAllTerminal>>,
 );
 
-/// Means for transacting assets on this chain.
+/// Means for transacting the native token of this chain.
 pub type LocalAssetTransactor = FungibleAdapter<
     // Use this currency:
     Balances,
     // Use this currency when it is a fungible asset matching the given location or name:
-    IsConcrete<RelayLocation>,
+    IsConcrete<HereLocation>,
     // Do a simple punn to convert an AccountId32 Location into a native chain account ID:
     LocationToAccountId,
     // Our chain's account ID type (we can't get away without mentioning it explicitly):
@@ -55,6 +77,143 @@ pub type LocalAssetTransactor = FungibleAdapter<
     (),
 >;
 
+/// Locations of assets issued by sibling parachains.
+pub struct SiblingAssets;
+impl Contains<Location> for SiblingAssets {
+    fn contains(location: &Location) -> bool {
+        matches!(location.unpack(), (1, [Parachain(_), ..]))
+    }
+}
+
+/// Matches the assets of sibling parachains registered in `ForeignAssets`, whose ids are their
+/// locations.
+pub type ForeignAssetsConvertedConcreteId = MatchedConvertedConcreteId<
+    xcm::v3::Location,
+    Balance,
+    SiblingAssets,
+    V4V3LocationConverter,
+    TryConvertInto,
+>;
+
+parameter_types! {
+    pub CheckingAccount: AccountId = PolkadotXcm::check_account();
+}
+
+/// Matches the relay chain token, once governance registered it in `ForeignAssets` under
+/// [`RelayTokenId`]. Until then, the relay chain token that arrives is trapped, and can be claimed
+/// after the registration.
+pub type RelayTokenConvertedConcreteId = MatchedConvertedConcreteId<
+    xcm::v3::Location,
+    Balance,
+    Equals<RelayLocation>,
+    V4V3LocationConverter,
+    TryConvertInto,
+>;
+
+/// Means for transacting the relay chain token.
+///
+/// The relay chain token is minted on the relay chain. Without the `relay-teleports` feature it
+/// arrives and leaves by reserve transfer, with the relay chain as reserve. With the feature it
+/// is teleported instead: teleports in are credited to the checking account and teleports out are
+/// debited from it, so no more leaves by teleport than arrived by teleport.
+pub type RelayTokenTransactor = FungiblesAdapter<
+    // Use this fungibles implementation:
+    ForeignAssets,
+    // Use this currency when it is the relay chain token:
+    RelayTokenConvertedConcreteId,
+    // Convert an XCM Location into a local account id:
+    LocationToAccountId,
+    // Our chain's account ID type (we can't get away without mentioning it explicitly):
+    AccountId,
+    // The relay chain token is minted elsewhere.
+    NonLocalMint<Equals<RelayTokenId>>,
+    // The account to use for tracking teleports.
+    CheckingAccount,
+>;
+
+/// Means for transacting the assets of sibling parachains.
+pub type ForeignAssetsTransactor = FungiblesAdapter<
+    // Use this fungibles implementation:
+    ForeignAssets,
+    // Use this currency when it is a registered sibling asset:
+    ForeignAssetsConvertedConcreteId,
+    // Convert an XCM Location into a local account id:
+    LocationToAccountId,
+    // Our chain's account ID type (we can't get away without mentioning it explicitly):
+    AccountId,
+    // We don't track any teleports of foreign assets.
+    NoChecking,
+    // The account to use for tracking teleports.
+    CheckingAccount,
+>;
+
+/// Means for transacting assets on this chain.
+pub type AssetTransactors = (
+    LocalAssetTransactor,
+    RelayTokenTransactor,
+    ForeignAssetsTransactor,
+);
+
+#[cfg(not(feature = "relay-teleports"))]
+parameter_types! {
+    pub RelayTokenFromRelay: (AssetFilter, Location) = (
+        Wild(AllOf { id: AssetId(RelayLocation::get()), fun: WildFungible }),
+        RelayLocation::get(),
+    );
+}
+
+/// Assets of sibling parachains are accepted as reserve transfers from the chain that issues them.
+pub struct ReserveAssetsFromSiblings;
+impl ContainsPair<Asset, Location> for ReserveAssetsFromSiblings {
+    fn contains(asset: &Asset, origin: &Location) -> bool {
+        matches!(origin.unpack(), (1, [Parachain(_)])) && asset.id.0.starts_with(origin)
+    }
+}
+
+/// Prices execution in a foreign asset at the rate transaction fees are paid in it.
+///
+/// The fee is converted with [`NativeToForeignAssetBalance`], so only sufficient assets pay for
+/// execution.
+pub struct ForeignAssetFeeCharger;
+impl ChargeWeightInFungibles<AccountId, ForeignAssets> for ForeignAssetFeeCharger {
+    fn charge_weight_in_fungibles(
+        asset_id: xcm::v3::Location,
+        weight: Weight,
+    ) -> Result<Balance, XcmError> {
+        let fee = WeightToFee::weight_to_fee(&weight);
+        NativeToForeignAssetBalance::to_asset_balance(fee, asset_id)
+            .map_err(|_| XcmError::TooExpensive)
+    }
+}
+
+/// The reserves whose transfers are trusted: the relay chain for its native token, and sibling
+/// parachains for their own assets.
+///
+/// The relay chain token goes back the same way: `pallet_xcm` burns it here and has the relay
+/// chain withdraw it from this chain's sovereign account there. The reserve transfer instructions
+/// that [`DenyReserveTransferToRelayChain`] denies towards the relay chain aren't used for this,
+/// so the barrier only stops transfers of other assets to the relay chain.
+#[cfg(not(feature = "relay-teleports"))]
+pub type TrustedReserves = (Case<RelayTokenFromRelay>, ReserveAssetsFromSiblings);
+/// With the `relay-teleports` feature the relay chain token is teleported, see
+/// [`TrustedTeleporters`], so only sibling parachains are trusted as reserves, for their own
+/// assets.
+#[cfg(feature = "relay-teleports")]
+pub type TrustedReserves = ReserveAssetsFromSiblings;
+
+/// With the `relay-teleports` feature, the relay chain and system parachains are trusted to
+/// teleport the relay chain token. The relay chain only trusts system parachains in turn, so this
+/// is meant for chains registered as such.
+#[cfg(feature = "relay-teleports")]
+pub type TrustedTeleporters = ConcreteAssetFromSystem<RelayLocation>;
+/// Teleports are disabled without the `relay-teleports` feature.
+#[cfg(not(feature = "relay-teleports"))]
+pub type TrustedTeleporters = ();
+
+parameter_types! {
+    pub XcmAssetFeesReceiver: Option<AccountId> = Some(Treasury::account_id());
+}
+
 /// This is the type we use to convert an (incoming) XCM origin into a local `Origin` instance,
 /// ready for dispatching a transaction with Xcm's `Transact`. There is an `OriginKind` which can
 /// biases the kind of local `Origin` it will become.
@@ -77,12 +236,16 @@ pub type XcmOriginToTransactDispatchOrigin = (
 );
 
 parameter_types! {
-    // One XCM operation is 1_000_000_000 weight - almost certainly a conservative estimate.
-    pub UnitWeightCost: Weight = Weight::from_parts(1_000_000_000, 64 * 1024);
     pub const MaxInstructions: u32 = 100;
     pub const MaxAssetsIntoHolding: u32 = 64;
 }
 
+/// Weighs messages with the `pallet_xcm_benchmarks` weight of each instruction. The executor and
+/// `pallet_xcm` weigh alike, so what `pallet_xcm` estimates for a message is what the executor
+/// charges.
+pub type XcmWeigher =
+    WeightInfoBounds<crate::weights::xcm::XcmWeight<RuntimeCall>, RuntimeCall, MaxInstructions>;
+
 pub struct ParentOrParentsExecutivePlurality;
 impl Contains<Location> for ParentOrParentsExecutivePlurality {
     fn contains(location: &Location) -> bool {
@@ -100,38 +263,115 @@ impl Contains<Location> for ParentOrParentsExecutivePlurality {
     }
 }
 
+/// Denies moving assets into the sovereign account of the relay chain.
+///
+/// `pallet_xcm` starts a reserve transfer of the native token to the relay chain this way, and
+/// sends the relay chain the deposit itself, past [`DenyReserveTransferToRelayChain`]. The relay
+/// chain is no reserve of the native token and wouldn't credit the beneficiary, so the transfer is
+/// refused before the token leaves.
+pub struct DenyTransfersToRelayChain;
+impl ShouldExecute for DenyTransfersToRelayChain {
+    fn should_execute<RuntimeCall>(
+        _origin: &Location,
+        message: &mut [Instruction<RuntimeCall>],
+        _max_weight: Weight,
+        _properties: &mut Properties,
+    ) -> Result<(), ProcessMessageError> {
+        let to_relay_chain = message.iter().any(|instruction| {
+            matches!(
+                instruction,
+                TransferAsset {
+                    beneficiary: Location {
+                        parents: 1,
+                        interior: Here
+                    },
+                    ..
+                }
+            )
+        });
+        if to_relay_chain {
+            return Err(ProcessMessageError::Unsupported);
+        }
+        Ok(())
+    }
+}
+
+// A tuple of barriers passes a message any of them passes, so each denial wraps the rest.
 pub type Barrier = TrailingSetTopicAsId<
     DenyThenTry<
         DenyReserveTransferToRelayChain,
-        (
-            TakeWeightCredit,
-            WithComputedOrigin<
-                (
-                    AllowTopLevelPaidExecutionFrom<Everything>,
-                    AllowExplicitUnpaidExecutionFrom<ParentOrParentsExecutivePlurality>,
-                    // ^^^ Parent and its exec plurality get free execution
-                ),
-                UniversalLocation,
-                ConstU32<8>,
-            >,
-        ),
+        DenyThenTry<
+            DenyTransfersToRelayChain,
+            (
+                TakeWeightCredit,
+                WithComputedOrigin<
+                    (
+                        AllowTopLevelPaidExecutionFrom<Everything>,
+                        AllowExplicitUnpaidExecutionFrom<ParentOrParentsExecutivePlurality>,
+                        // ^^^ Parent and its exec plurality get free execution
+                    ),
+                    UniversalLocation,
+                    ConstU32<8>,
+                >,
+            ),
+        >,
     >,
 >;
 
+/// The calls `Transact` may dispatch: staking, and batches of staking calls.
+///
+/// Staking on this chain is collator selection and session keys, the calls of
+/// `ProxyType::Staking`. The staking and nomination pools pallets' calls belong here once the
+/// runtime has them.
+pub struct SafeCallFilter;
+impl Contains<RuntimeCall> for SafeCallFilter {
+    fn contains(call: &RuntimeCall) -> bool {
+        match call {
+            RuntimeCall::CollatorSelection(..) | RuntimeCall::Session(..) => true,
+            // Batches are as safe as the calls in them.
+            RuntimeCall::Utility(
+                pallet_utility::Call::batch { calls }
+                | pallet_utility::Call::batch_all { calls }
+                | pallet_utility::Call::force_batch { calls },
+            ) => calls.iter().all(Self::contains),
+            _ => false,
+        }
+    }
+}
+
 pub struct XcmConfig;
 impl xcm_executor::Config for XcmConfig {
     type RuntimeCall = RuntimeCall;
     type XcmSender = XcmRouter;
     // How to withdraw and deposit an asset.
-    type AssetTransactor = LocalAssetTransactor;
+    type AssetTransactor = AssetTransactors;
     type OriginConverter = XcmOriginToTransactDispatchOrigin;
-    type IsReserve = NativeAsset;
-    type IsTeleporter = (); // Teleporting is disabled.
+    type IsReserve = TrustedReserves;
+    type IsTeleporter = TrustedTeleporters;
     type UniversalLocation = UniversalLocation;
     type Barrier = Barrier;
-    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-    type Trader =
-        UsingComponents<WeightToFee, RelayLocation, AccountId, Balances, ToAuthor<Runtime>>;
+    type Weigher = XcmWeigher;
+    // Execution is priced from the weight of the message, with the fee curve of transactions.
+    // Execution fees in the native token are split between the treasury and the block author, like
+    // transaction fees. Fees paid in foreign assets, the relay chain token among them, go to the
+    // treasury.
+    type Trader = (
+        UsingComponents<WeightToFee, HereLocation, AccountId, Balances, DealWithFees>,
+        TakeFirstAssetTrader<
+            AccountId,
+            ForeignAssetFeeCharger,
+            (
+                RelayTokenConvertedConcreteId,
+                ForeignAssetsConvertedConcreteId,
+            ),
+            ForeignAssets,
+            XcmFeesTo32ByteAccount<
+                (RelayTokenTransactor, ForeignAssetsTransactor),
+                AccountId,
+                XcmAssetFeesReceiver,
+            >,
+        >,
+    );
     type ResponseHandler = PolkadotXcm;
     type AssetTrap = PolkadotXcm;
     type AssetClaims = PolkadotXcm;
@@ -144,7 +384,7 @@ impl xcm_executor::Config for XcmConfig {
     type MessageExporter = ();
     type UniversalAliases = Nothing;
     type CallDispatcher = RuntimeCall;
-    type SafeCallFilter = Everything;
+    type SafeCallFilter = SafeCallFilter;
     type Aliasers = Nothing;
     type TransactionalProcessor = FrameTransactionalProcessor;
 }
@@ -171,8 +411,8 @@ impl pallet_xcm::Config for Runtime {
     // Needs to be `Everything` for local testing.
     type XcmExecutor = XcmExecutor<XcmConfig>;
     type XcmTeleportFilter = Everything;
-    type XcmReserveTransferFilter = Nothing;
-    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+    type XcmReserveTransferFilter = Everything;
+    type Weigher = XcmWeigher;
     type UniversalLocation = UniversalLocation;
     type RuntimeOrigin = RuntimeOrigin;
     type RuntimeCall = RuntimeCall;
@@ -185,8 +425,8 @@ impl pallet_xcm::Config for Runtime {
     type TrustedLockers = ();
     type SovereignAccountOf = LocationToAccountId;
     type MaxLockers = ConstU32<8>;
-    type WeightInfo = pallet_xcm::TestWeightInfo;
-    type AdminOrigin = EnsureRoot<AccountId>;
+    type WeightInfo = crate::weights::pallet_xcm::WeightInfo<Runtime>;
+    type AdminOrigin = GeneralAdminOrRoot;
     type MaxRemoteLockConsumers = ConstU32<0>;
     type RemoteLockConsumerIdentifier = ();
 }
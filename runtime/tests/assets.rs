@@ -0,0 +1,160 @@
+//! Local and foreign assets, and transaction fees paid in foreign assets.
+
+mod common;
+
+use codec::Encode;
+use common::{events, new_test_ext, signed_extrinsic_paying_in, split_fee, Chain};
+use frame_support::{dispatch::GetDispatchInfo, traits::fungibles};
+use parachain_template_runtime::{
+    governance::pallet_custom_origins::Origin, AccountId, AssetDeposit, Assets, Balances,
+    ForeignAssets, RuntimeCall, RuntimeEvent, RuntimeOrigin, System, TransactionPayment, Treasury,
+    EXISTENTIAL_DEPOSIT, UNIT,
+};
+use sp_keyring::Sr25519Keyring::{Alice, Bob, Eve};
+use sp_runtime::{traits::BadOrigin, DispatchError};
+use xcm::v3::Location;
+
+/// The slot the first block is authored in.
+const GENESIS_SLOT: u64 = 100;
+
+/// Registers the relay chain token, owned by Alice so the tests can mint it.
+fn register_relay_token(min_balance: u128) {
+    ForeignAssets::force_create(
+        Origin::GeneralAdmin.into(),
+        Location::parent(),
+        Alice.to_account_id().into(),
+        true,
+        min_balance,
+    )
+    .expect("the general admin registers foreign assets");
+}
+
+/// Mints relay chain tokens to `who`.
+fn mint_relay_token(who: AccountId, amount: u128) {
+    ForeignAssets::mint(
+        RuntimeOrigin::signed(Alice.to_account_id()),
+        Location::parent(),
+        who.into(),
+        amount,
+    )
+    .expect("Alice is the issuer");
+}
+
+#[test]
+fn anyone_can_create_local_assets() {
+    new_test_ext().execute_with(|| {
+        let alice = Alice.to_account_id();
+        Assets::create(
+            RuntimeOrigin::signed(alice.clone()),
+            1.into(),
+            alice.clone().into(),
+            UNIT,
+        )
+        .expect("Alice can pay the asset deposit");
+        assert_eq!(Balances::reserved_balance(&alice), AssetDeposit::get());
+
+        Assets::mint(
+            RuntimeOrigin::signed(alice.clone()),
+            1.into(),
+            Eve.to_account_id().into(),
+            10 * UNIT,
+        )
+        .expect("Alice is the issuer");
+        assert_eq!(Assets::balance(1, Eve.to_account_id()), 10 * UNIT);
+    });
+}
+
+#[test]
+fn foreign_assets_are_registered_by_governance() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            ForeignAssets::create(
+                RuntimeOrigin::signed(Alice.to_account_id()),
+                Location::parent(),
+                Alice.to_account_id().into(),
+                UNIT,
+            ),
+            Err(DispatchError::from(BadOrigin))
+        );
+        assert_eq!(
+            ForeignAssets::force_create(
+                Origin::StakingAdmin.into(),
+                Location::parent(),
+                Alice.to_account_id().into(),
+                true,
+                UNIT,
+            ),
+            Err(DispatchError::from(BadOrigin))
+        );
+        register_relay_token(UNIT);
+        assert!(<ForeignAssets as fungibles::Inspect<AccountId>>::asset_exists(Location::parent()));
+    });
+}
+
+#[test]
+fn fees_can_be_paid_in_relay_tokens_at_the_configured_rate() {
+    new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+        chain.author_block(GENESIS_SLOT, Alice, vec![]);
+
+        // One unit of the native token is worth two of the relay chain token.
+        register_relay_token(2 * EXISTENTIAL_DEPOSIT);
+        // The treasury and the author hold some already, so their share of the fee isn't below
+        // the minimum balance.
+        mint_relay_token(Bob.to_account_id(), UNIT);
+        mint_relay_token(Treasury::account_id(), UNIT);
+
+        // Eve arrives with nothing but relay chain tokens.
+        let eve = Eve.to_account_id();
+        Balances::transfer_all(
+            RuntimeOrigin::signed(eve.clone()),
+            Alice.to_account_id().into(),
+            false,
+        )
+        .expect("Eve's balance is free");
+        assert!(!System::account_exists(&eve));
+        mint_relay_token(eve.clone(), 100 * UNIT);
+
+        let remark = signed_extrinsic_paying_in(
+            Eve,
+            RuntimeCall::System(frame_system::Call::remark {
+                remark: vec![0; 32],
+            }),
+            Some(Location::parent()),
+        );
+        let len = remark.encoded_size() as u32;
+        let native_fee = TransactionPayment::compute_fee(len, &remark.get_dispatch_info(), 0);
+        chain.author_block(GENESIS_SLOT + 1, Bob, vec![remark]);
+
+        let fee = events()
+            .into_iter()
+            .find_map(|event| match event {
+                RuntimeEvent::AssetTxPayment(pallet_asset_tx_payment::Event::AssetTxFeePaid {
+                    who,
+                    actual_fee,
+                    asset_id,
+                    ..
+                }) if who == eve => {
+                    assert_eq!(asset_id, Some(Location::parent()));
+                    Some(actual_fee)
+                }
+                _ => None,
+            })
+            .expect("the remark paid a fee");
+        assert_eq!(fee, 2 * native_fee);
+
+        assert_eq!(
+            ForeignAssets::balance(Location::parent(), &eve),
+            100 * UNIT - fee
+        );
+        let (to_treasury, to_author) = split_fee(fee);
+        assert_eq!(
+            ForeignAssets::balance(Location::parent(), Treasury::account_id()),
+            UNIT + to_treasury
+        );
+        assert_eq!(
+            ForeignAssets::balance(Location::parent(), Bob.to_account_id()),
+            UNIT + to_author
+        );
+    });
+}
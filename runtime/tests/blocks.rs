@@ -0,0 +1,281 @@
+//! Blocks authored on top of the genesis of `testnet_genesis`, the way collators author them.
+
+mod common;
+
+use codec::Encode;
+use common::{babe_id, events, new_test_ext, pre_header, seal, signed_extrinsic, split_fee, Chain};
+use cumulus_primitives_core::AggregateMessageOrigin;
+use frame_support::traits::{ExecuteBlock, KeyOwnerProofSystem};
+use parachain_template_runtime::{
+    governance::pallet_custom_origins, AccountId, Babe, Balances, Block, EpochDuration, Executive,
+    ForeignAssets, Historical, PalletBabeBlockExecutor, Runtime, RuntimeCall, RuntimeEvent,
+    RuntimeOrigin, Session, Treasury, EXISTENTIAL_DEPOSIT, UNIT,
+};
+use sp_consensus_babe::{ConsensusLog, EquivocationProof, Slot, BABE_ENGINE_ID};
+use sp_keyring::Sr25519Keyring::{Alice, Bob, Charlie};
+use sp_runtime::traits::{Block as _, Header as _};
+use xcm::{latest::prelude::*, VersionedXcm};
+
+/// The slot the first block is authored in.
+const GENESIS_SLOT: u64 = 100;
+
+/// Whether `block` announces the authorities of the next epoch.
+fn announces_next_epoch(block: &Block) -> bool {
+    block.header().digest().logs().iter().any(|item| {
+        matches!(
+            item.consensus_try_to::<ConsensusLog>(&BABE_ENGINE_ID),
+            Some(ConsensusLog::NextEpochData(_))
+        )
+    })
+}
+
+#[test]
+fn authored_blocks_pass_validation() {
+    let blocks = new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+        vec![
+            chain.author_block(GENESIS_SLOT, Alice, vec![]),
+            chain.author_block(GENESIS_SLOT + 1, Bob, vec![]),
+            // Enacts the next epoch, which is authored by the next authorities.
+            chain.author_block(GENESIS_SLOT + EpochDuration::get(), Alice, vec![]),
+        ]
+    });
+
+    new_test_ext().execute_with(|| {
+        for block in blocks {
+            PalletBabeBlockExecutor::<Runtime, Executive>::execute_block(block);
+        }
+        assert_eq!(Babe::epoch_index(), 1);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Invalid BABE seal")]
+fn blocks_sealed_by_someone_else_are_rejected() {
+    let block = new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+        let (mut header, extrinsics) = chain
+            .author_block(GENESIS_SLOT, Alice, vec![])
+            .deconstruct();
+        header.digest_mut().pop();
+        seal(&mut header, Charlie);
+        Block::new(header, extrinsics)
+    });
+
+    new_test_ext().execute_with(|| {
+        PalletBabeBlockExecutor::<Runtime, Executive>::execute_block(block);
+    });
+}
+
+#[test]
+fn epoch_change_rotates_session() {
+    new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+
+        let block = chain.author_block(GENESIS_SLOT, Alice, vec![]);
+        assert!(announces_next_epoch(&block));
+        assert_eq!(Babe::genesis_slot(), Slot::from(GENESIS_SLOT));
+        assert_eq!(Session::current_index(), 0);
+
+        let block = chain.author_block(GENESIS_SLOT + EpochDuration::get() - 1, Bob, vec![]);
+        assert!(!announces_next_epoch(&block));
+        assert_eq!(Babe::epoch_index(), 0);
+        assert_eq!(Session::current_index(), 0);
+
+        let block = chain.author_block(GENESIS_SLOT + EpochDuration::get(), Alice, vec![]);
+        assert!(announces_next_epoch(&block));
+        assert_eq!(Babe::epoch_index(), 1);
+        assert_eq!(Session::current_index(), 1);
+        assert!(
+            events().contains(&RuntimeEvent::Session(pallet_session::Event::NewSession {
+                session_index: 1
+            }))
+        );
+        // Collator selection sorts the invulnerables by account id.
+        assert_eq!(
+            Session::validators(),
+            vec![Bob.to_account_id(), Alice.to_account_id()]
+        );
+    });
+}
+
+#[test]
+fn skipped_epochs_still_rotate_one_session() {
+    new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+        chain.author_block(GENESIS_SLOT, Alice, vec![]);
+
+        chain.author_block(GENESIS_SLOT + 3 * EpochDuration::get(), Bob, vec![]);
+        assert_eq!(Babe::epoch_index(), 3);
+        assert_eq!(Session::current_index(), 1);
+        assert_eq!(
+            Babe::current_epoch_start(),
+            Slot::from(GENESIS_SLOT + 3 * EpochDuration::get())
+        );
+    });
+}
+
+#[test]
+fn transaction_fees_are_split_between_treasury_and_author() {
+    new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+        chain.author_block(GENESIS_SLOT, Alice, vec![]);
+
+        let alice = Alice.to_account_id();
+        let bob = Bob.to_account_id();
+        let charlie = Charlie.to_account_id();
+        let treasury = Treasury::account_id();
+        let alice_balance = Balances::free_balance(&alice);
+        let bob_balance = Balances::free_balance(&bob);
+        let charlie_balance = Balances::free_balance(&charlie);
+        let treasury_balance = Balances::free_balance(&treasury);
+        let total_issuance = Balances::total_issuance();
+
+        let transfer = signed_extrinsic(
+            Alice,
+            RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+                dest: charlie.clone().into(),
+                value: UNIT,
+            }),
+        );
+        chain.author_block(GENESIS_SLOT + 1, Bob, vec![transfer]);
+
+        let fee = events()
+            .into_iter()
+            .find_map(|event| match event {
+                RuntimeEvent::TransactionPayment(
+                    pallet_transaction_payment::Event::TransactionFeePaid {
+                        who, actual_fee, ..
+                    },
+                ) if who == alice => Some(actual_fee),
+                _ => None,
+            })
+            .expect("the transfer paid a fee");
+
+        assert!(fee > 0);
+        assert_eq!(Balances::free_balance(&alice), alice_balance - UNIT - fee);
+        assert_eq!(Balances::free_balance(&charlie), charlie_balance + UNIT);
+        let (to_treasury, to_author) = split_fee(fee);
+        assert_eq!(
+            Balances::free_balance(&treasury),
+            treasury_balance + to_treasury
+        );
+        assert_eq!(Balances::free_balance(&bob), bob_balance + to_author);
+        assert_eq!(Balances::total_issuance(), total_issuance);
+    });
+}
+
+#[test]
+fn xcm_execution_fees_in_the_relay_chain_token_go_to_the_treasury() {
+    new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+
+        // The relay chain token is held in `ForeignAssets` once governance registered it, worth as
+        // much as the native token.
+        ForeignAssets::force_create(
+            pallet_custom_origins::Origin::GeneralAdmin.into(),
+            xcm::v3::Location::parent(),
+            Alice.to_account_id().into(),
+            true,
+            EXISTENTIAL_DEPOSIT,
+        )
+        .expect("the general admin registers foreign assets");
+
+        let charlie = Charlie.to_account_id();
+        let treasury = Treasury::account_id();
+        let relay_token =
+            |who: &AccountId| ForeignAssets::balance(xcm::v3::Location::parent(), who);
+
+        let assets: Assets = (Parent, 10 * UNIT).into();
+        let message: Xcm<()> = Xcm(vec![
+            ReserveAssetDeposited(assets.clone()),
+            ClearOrigin,
+            BuyExecution {
+                fees: (Parent, 10 * UNIT).into(),
+                weight_limit: Unlimited,
+            },
+            DepositAsset {
+                assets: AllCounted(1).into(),
+                beneficiary: AccountId32 {
+                    network: None,
+                    id: charlie.clone().into(),
+                }
+                .into(),
+            },
+        ]);
+        chain.send_downward(VersionedXcm::from(message).encode());
+        chain.author_block(GENESIS_SLOT, Alice, vec![]);
+        chain.author_block(GENESIS_SLOT + 1, Bob, vec![]);
+
+        let fee = 10 * UNIT - relay_token(&charlie);
+        assert!(fee > 0);
+        assert_eq!(relay_token(&treasury), fee);
+    });
+}
+
+#[test]
+fn equivocations_are_reported_as_offences() {
+    new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+        chain.author_block(GENESIS_SLOT, Alice, vec![]);
+
+        // Two different blocks claiming the same slot, both sealed by Bob.
+        let slot = (GENESIS_SLOT + 1).into();
+        let parent_hash = chain.head().hash();
+        let mut first_header = pre_header(2, parent_hash, slot, Bob);
+        seal(&mut first_header, Bob);
+        let mut second_header = pre_header(2, parent_hash, slot, Bob);
+        second_header.set_state_root([1; 32].into());
+        seal(&mut second_header, Bob);
+
+        let equivocation_proof = EquivocationProof {
+            offender: babe_id(Bob),
+            slot,
+            first_header,
+            second_header,
+        };
+        let key_owner_proof = Historical::prove((sp_consensus_babe::KEY_TYPE, babe_id(Bob)))
+            .expect("Bob is a validator of the current session");
+
+        Babe::report_equivocation_unsigned(
+            RuntimeOrigin::none(),
+            Box::new(equivocation_proof),
+            key_owner_proof,
+        )
+        .expect("the equivocation proof is valid");
+
+        assert!(events().iter().any(|event| matches!(
+            event,
+            RuntimeEvent::Offences(pallet_offences::Event::Offence { kind, .. })
+                if kind == b"babe:equivocatio"
+        )));
+    });
+}
+
+#[test]
+fn downward_messages_are_executed() {
+    new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+
+        let message: Xcm<()> = Xcm(vec![
+            UnpaidExecution {
+                weight_limit: Unlimited,
+                check_origin: None,
+            },
+            ClearOrigin,
+        ]);
+        chain.send_downward(VersionedXcm::from(message).encode());
+        chain.author_block(GENESIS_SLOT, Alice, vec![]);
+
+        // Messages received in a block are processed at the start of the next one.
+        chain.author_block(GENESIS_SLOT + 1, Bob, vec![]);
+        assert!(events().iter().any(|event| matches!(
+            event,
+            RuntimeEvent::MessageQueue(pallet_message_queue::Event::Processed {
+                origin: AggregateMessageOrigin::Parent,
+                success: true,
+                ..
+            })
+        )));
+    });
+}
@@ -0,0 +1,302 @@
+//! Genesis state and block authoring shared by the runtime integration tests.
+//!
+//! Blocks are authored the way the collator does it: the BABE pre-digest goes into the header,
+//! the parachain system and timestamp inherents are applied first and the header is sealed by the
+//! slot's author once the block is finalized.
+
+#![allow(dead_code)]
+
+use codec::Encode;
+use cumulus_primitives_core::{
+    relay_chain::HeadData, InboundDownwardMessage, PersistedValidationData,
+};
+use cumulus_primitives_parachain_inherent::{MessageQueueChain, ParachainInherentData};
+use cumulus_test_relay_sproof_builder::RelayStateSproofBuilder;
+use parachain_template_runtime::{
+    genesis_config_presets, Babe, Block, Executive, Header, RuntimeCall, RuntimeEvent,
+    RuntimeGenesisConfig, SignedExtra, System, TreasuryFeeShare, UncheckedExtrinsic, SLOT_DURATION,
+};
+use sp_consensus_babe::{
+    digests::{CompatibleDigestItem, PreDigest, SecondaryPlainPreDigest},
+    AuthorityId as BabeId, Slot,
+};
+use sp_core::Pair;
+use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+use sp_runtime::{
+    generic::{Digest, Era, SignedPayload},
+    traits::{Block as _, Header as _},
+    BuildStorage, DigestItem, MultiSignature,
+};
+
+/// The collators of the test network, which are also its BABE authorities.
+pub const COLLATORS: [Sr25519Keyring; 2] = [Sr25519Keyring::Alice, Sr25519Keyring::Bob];
+
+/// The relay chain produces a block every other parachain slot.
+const RELAY_SLOT_DURATION: u64 = 6000;
+
+/// Merge the JSON `patch` into `base`, the way chain specs apply genesis config patches.
+fn merge(base: &mut serde_json::Value, patch: serde_json::Value) {
+    match (base, patch) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge(base.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+/// Ferdie's endowment vests over this many blocks from genesis, a tenth of it is liquid.
+pub const FERDIE_VESTING_BLOCKS: u32 = 1_000;
+
+/// The genesis state of a network run by [`COLLATORS`], with all well-known accounts endowed.
+///
+/// Ferdie's endowment vests over [`FERDIE_VESTING_BLOCKS`].
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let invulnerables = COLLATORS
+        .iter()
+        .zip([Ed25519Keyring::Alice, Ed25519Keyring::Bob])
+        .map(|(babe, grandpa)| {
+            (
+                babe.to_account_id(),
+                babe.public().into(),
+                grandpa.public().into(),
+            )
+        })
+        .collect();
+    let endowed = Sr25519Keyring::iter()
+        .map(|keyring| keyring.to_account_id())
+        .collect();
+    let vesting = vec![(
+        Sr25519Keyring::Ferdie.to_account_id(),
+        0,
+        FERDIE_VESTING_BLOCKS,
+        genesis_config_presets::ENDOWMENT / 10,
+    )];
+    let patch = genesis_config_presets::testnet_genesis(
+        invulnerables,
+        endowed,
+        vesting,
+        genesis_config_presets::PARACHAIN_ID.into(),
+    );
+
+    let mut config = serde_json::to_value(RuntimeGenesisConfig::default())
+        .expect("the default genesis config serializes");
+    merge(&mut config, patch);
+    let config: RuntimeGenesisConfig =
+        serde_json::from_value(config).expect("the genesis config patch is valid");
+
+    sp_io::TestExternalities::new(config.build_storage().expect("genesis storage builds"))
+}
+
+/// The BABE key of `collator`.
+pub fn babe_id(collator: Sr25519Keyring) -> BabeId {
+    collator.public().into()
+}
+
+/// Seal `header` with a BABE seal by `author`.
+pub fn seal(header: &mut Header, author: Sr25519Keyring) {
+    let signature = author.pair().sign(header.hash().as_ref());
+    header
+        .digest_mut()
+        .push(DigestItem::babe_seal(signature.into()));
+}
+
+/// A header at `slot` whose pre-digest claims the slot for `author`, without a seal.
+pub fn pre_header(
+    number: u32,
+    parent_hash: sp_core::H256,
+    slot: Slot,
+    author: Sr25519Keyring,
+) -> Header {
+    let authority_index = Babe::authorities()
+        .iter()
+        .position(|(id, _)| *id == babe_id(author))
+        .expect("author is a BABE authority") as u32;
+    let pre_digest = PreDigest::SecondaryPlain(SecondaryPlainPreDigest {
+        authority_index,
+        slot,
+    });
+
+    Header::new(
+        number,
+        Default::default(),
+        Default::default(),
+        parent_hash,
+        Digest {
+            logs: vec![DigestItem::babe_pre_digest(pre_digest)],
+        },
+    )
+}
+
+/// A signed extrinsic of `signer`, using its current nonce.
+pub fn signed_extrinsic(signer: Sr25519Keyring, call: RuntimeCall) -> UncheckedExtrinsic {
+    signed_extrinsic_paying_in(signer, call, None)
+}
+
+/// A signed extrinsic of `signer` whose fees are paid in `fee_asset`, or in the native token if
+/// it's `None`.
+pub fn signed_extrinsic_paying_in(
+    signer: Sr25519Keyring,
+    call: RuntimeCall,
+    fee_asset: Option<xcm::v3::Location>,
+) -> UncheckedExtrinsic {
+    let account = signer.to_account_id();
+    let extra: SignedExtra = (
+        frame_system::CheckNonZeroSender::new(),
+        frame_system::CheckSpecVersion::new(),
+        frame_system::CheckTxVersion::new(),
+        frame_system::CheckGenesis::new(),
+        frame_system::CheckEra::from(Era::Immortal),
+        frame_system::CheckNonce::from(System::account_nonce(&account)),
+        frame_system::CheckWeight::new(),
+        pallet_asset_tx_payment::ChargeAssetTxPayment::from(0, fee_asset),
+        cumulus_primitives_storage_weight_reclaim::StorageWeightReclaim::new(),
+    );
+    let payload = SignedPayload::new(call, extra).expect("additional signed data is available");
+    let signature = payload.using_encoded(|payload| signer.sign(payload));
+    let (call, extra, _) = payload.deconstruct();
+
+    UncheckedExtrinsic::new_signed(
+        call,
+        account.into(),
+        MultiSignature::Sr25519(signature),
+        extra,
+    )
+}
+
+/// The shares of `fee` the treasury and the block author receive.
+pub fn split_fee(fee: u128) -> (u128, u128) {
+    let to_treasury = fee * u128::from(TreasuryFeeShare::get().deconstruct()) / 100;
+    (to_treasury, fee - to_treasury)
+}
+
+/// Events deposited in the current block.
+pub fn events() -> Vec<RuntimeEvent> {
+    System::events()
+        .into_iter()
+        .map(|record| record.event)
+        .collect()
+}
+
+/// Authors blocks on top of the genesis state, or whichever block was authored last.
+///
+/// Must be used within the externalities the chain is built on.
+pub struct Chain {
+    parent: Header,
+    relay_parent_number: u32,
+    dmq_mqc: MessageQueueChain,
+    downward_messages: Vec<InboundDownwardMessage>,
+}
+
+impl Default for Chain {
+    fn default() -> Self {
+        Self {
+            parent: Header::new(
+                0,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            ),
+            relay_parent_number: 0,
+            dmq_mqc: MessageQueueChain::default(),
+            downward_messages: Vec::new(),
+        }
+    }
+}
+
+impl Chain {
+    /// The last authored block.
+    pub fn head(&self) -> &Header {
+        &self.parent
+    }
+
+    /// Send `message` from the relay chain, it is included with the next block.
+    pub fn send_downward(&mut self, message: Vec<u8>) {
+        let message = InboundDownwardMessage {
+            sent_at: self.relay_parent_number + 1,
+            msg: message,
+        };
+        self.dmq_mqc.extend_downward(&message);
+        self.downward_messages.push(message);
+    }
+
+    /// The inherent with the validation data of a relay parent whose slot is not past `slot`.
+    ///
+    /// The parent block is reported as included, so the unincluded segment never fills up.
+    fn validation_data(&mut self, slot: Slot) -> UncheckedExtrinsic {
+        self.relay_parent_number += 1;
+
+        let relay_slot = *slot * SLOT_DURATION / RELAY_SLOT_DURATION;
+        let sproof = RelayStateSproofBuilder {
+            para_id: genesis_config_presets::PARACHAIN_ID.into(),
+            current_slot: relay_slot.into(),
+            included_para_head: Some(HeadData(self.parent.encode())),
+            dmq_mqc_head: Some(self.dmq_mqc.head()),
+            ..Default::default()
+        };
+        let (relay_parent_storage_root, relay_chain_state) = sproof.into_state_root_and_proof();
+
+        let data = ParachainInherentData {
+            validation_data: PersistedValidationData {
+                parent_head: HeadData(self.parent.encode()),
+                relay_parent_number: self.relay_parent_number,
+                relay_parent_storage_root,
+                max_pov_size: 5 * 1024 * 1024,
+            },
+            relay_chain_state,
+            downward_messages: std::mem::take(&mut self.downward_messages),
+            horizontal_messages: Default::default(),
+        };
+
+        UncheckedExtrinsic::new_unsigned(RuntimeCall::ParachainSystem(
+            cumulus_pallet_parachain_system::Call::set_validation_data { data },
+        ))
+    }
+
+    /// Author the next block at `slot` by `author`, with `extrinsics` after the inherents.
+    pub fn author_block(
+        &mut self,
+        slot: u64,
+        author: Sr25519Keyring,
+        extrinsics: Vec<UncheckedExtrinsic>,
+    ) -> Block {
+        let number = self.parent.number + 1;
+        self.author_block_at(number, slot, author, extrinsics)
+    }
+
+    /// Author block `number` at `slot` by `author`, with `extrinsics` after the inherents.
+    ///
+    /// The block number may skip ahead of the parent to reach late blocks quickly.
+    pub fn author_block_at(
+        &mut self,
+        number: u32,
+        slot: u64,
+        author: Sr25519Keyring,
+        extrinsics: Vec<UncheckedExtrinsic>,
+    ) -> Block {
+        let slot = Slot::from(slot);
+        let header = pre_header(number, self.parent.hash(), slot, author);
+        Executive::initialize_block(&header);
+
+        let inherents = vec![
+            self.validation_data(slot),
+            UncheckedExtrinsic::new_unsigned(RuntimeCall::Timestamp(pallet_timestamp::Call::set {
+                now: *slot * SLOT_DURATION,
+            })),
+        ];
+        let extrinsics = inherents.into_iter().chain(extrinsics).collect::<Vec<_>>();
+        for extrinsic in &extrinsics {
+            Executive::apply_extrinsic(extrinsic.clone())
+                .expect("extrinsic is valid")
+                .expect("extrinsic dispatches");
+        }
+
+        let mut header = Executive::finalize_block();
+        seal(&mut header, author);
+        self.parent = header.clone();
+
+        Block::new(header, extrinsics)
+    }
+}
@@ -0,0 +1,114 @@
+//! Privileged calls dispatched through referenda instead of a sudo key.
+
+mod common;
+
+use common::{new_test_ext, Chain};
+use frame_support::traits::StorePreimage;
+use pallet_conviction_voting::{AccountVote, Conviction, Vote};
+use pallet_referenda::TracksInfo as _;
+use parachain_template_runtime::{
+    governance::{pallet_custom_origins::Origin, TracksInfo},
+    Balances, CollatorSelection, ConvictionVoting, OriginCaller, Preimage, Referenda, Runtime,
+    RuntimeCall, RuntimeOrigin, HOURS,
+};
+use sp_keyring::Sr25519Keyring::{self, Alice};
+use sp_runtime::{traits::BadOrigin, DispatchError};
+
+/// The slot the first block is authored in.
+const GENESIS_SLOT: u64 = 100;
+
+#[test]
+fn every_governance_origin_has_a_track() {
+    let origins = [
+        (OriginCaller::system(frame_system::RawOrigin::Root), 0),
+        (OriginCaller::Origins(Origin::WhitelistedCaller), 1),
+        (OriginCaller::Origins(Origin::StakingAdmin), 10),
+        (OriginCaller::Origins(Origin::Treasurer), 11),
+        (OriginCaller::Origins(Origin::GeneralAdmin), 14),
+        (OriginCaller::Origins(Origin::ReferendumCanceller), 20),
+        (OriginCaller::Origins(Origin::ReferendumKiller), 21),
+    ];
+    for (origin, track) in origins {
+        assert_eq!(TracksInfo::track_for(&origin), Ok(track));
+        assert!(TracksInfo::info(track).is_some());
+    }
+
+    let signed = OriginCaller::system(frame_system::RawOrigin::Signed(Alice.to_account_id()));
+    assert_eq!(TracksInfo::track_for(&signed), Err(()));
+}
+
+#[test]
+fn collator_selection_is_administered_by_the_staking_admin() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            CollatorSelection::set_desired_candidates(
+                RuntimeOrigin::signed(Alice.to_account_id()),
+                5
+            ),
+            Err(DispatchError::from(BadOrigin).into())
+        );
+        assert_eq!(
+            CollatorSelection::set_desired_candidates(Origin::GeneralAdmin.into(), 5),
+            Err(DispatchError::from(BadOrigin).into())
+        );
+        assert!(CollatorSelection::set_desired_candidates(Origin::StakingAdmin.into(), 5).is_ok());
+    });
+}
+
+#[test]
+fn approved_referendum_is_enacted_with_its_origin() {
+    new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+        chain.author_block(GENESIS_SLOT, Alice, vec![]);
+
+        let alice = Alice.to_account_id();
+        let call = RuntimeCall::CollatorSelection(
+            pallet_collator_selection::Call::set_desired_candidates { max: 5 },
+        );
+        let proposal = Preimage::bound(call).expect("the call is small enough to be bounded");
+        Referenda::submit(
+            RuntimeOrigin::signed(alice.clone()),
+            Box::new(OriginCaller::Origins(Origin::StakingAdmin)),
+            proposal,
+            frame_support::traits::schedule::DispatchTime::After(0),
+        )
+        .expect("anyone can submit a referendum");
+        let index = pallet_referenda::ReferendumCount::<Runtime>::get() - 1;
+        Referenda::place_decision_deposit(RuntimeOrigin::signed(alice), index)
+            .expect("Alice can pay the decision deposit");
+
+        // Everyone votes aye with all they have.
+        for voter in Sr25519Keyring::iter() {
+            let account = voter.to_account_id();
+            let vote = AccountVote::Standard {
+                vote: Vote {
+                    aye: true,
+                    conviction: Conviction::Locked1x,
+                },
+                balance: Balances::free_balance(&account),
+            };
+            ConvictionVoting::vote(RuntimeOrigin::signed(account), index, vote)
+                .expect("the referendum is ongoing");
+        }
+
+        // Prepare and confirm periods last hours. The scheduler only services the agenda of the
+        // block being authored, so skip ahead straight to the next block with something scheduled.
+        let mut number = 1;
+        while pallet_collator_selection::DesiredCandidates::<Runtime>::get() != 5 {
+            number = pallet_scheduler::Agenda::<Runtime>::iter_keys()
+                .filter(|&when| when > number)
+                .min()
+                .expect("the referendum has an alarm or enactment scheduled");
+            assert!(
+                number < 24 * HOURS,
+                "the referendum was not enacted within a day"
+            );
+            chain.author_block_at(number, GENESIS_SLOT + u64::from(number), Alice, vec![]);
+        }
+
+        assert!(matches!(
+            pallet_referenda::ReferendumInfoFor::<Runtime>::get(index),
+            Some(pallet_referenda::ReferendumInfo::Approved(..))
+        ));
+    });
+}
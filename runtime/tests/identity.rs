@@ -0,0 +1,135 @@
+//! On-chain identities, which tell nominators who runs a validator.
+
+mod common;
+
+use codec::Encode;
+use common::new_test_ext;
+use pallet_identity::{legacy::IdentityInfo, Data};
+use parachain_template_runtime::{
+    deposit, governance::pallet_custom_origins::Origin, staking_api, Balances, Identity,
+    MaxAdditionalFields, RuntimeOrigin,
+};
+use sp_keyring::Sr25519Keyring::{Alice, Bob, Charlie, Dave, Eve};
+use sp_runtime::{traits::BadOrigin, DispatchError};
+
+/// An identity with nothing but a display name.
+fn identity(display: &[u8]) -> Box<IdentityInfo<MaxAdditionalFields>> {
+    Box::new(IdentityInfo {
+        additional: Default::default(),
+        display: Data::Raw(
+            display
+                .to_vec()
+                .try_into()
+                .expect("the name is short enough"),
+        ),
+        legal: Data::None,
+        web: Data::None,
+        riot: Data::None,
+        email: Data::None,
+        pgp_fingerprint: None,
+        image: Data::None,
+        twitter: Data::None,
+    })
+}
+
+#[test]
+fn identity_deposit_follows_the_storage_deposit() {
+    new_test_ext().execute_with(|| {
+        let alice = Alice.to_account_id();
+        let info = identity(b"Alice");
+        let size = info.encoded_size() as u32;
+
+        Identity::set_identity(RuntimeOrigin::signed(alice.clone()), info)
+            .expect("Alice can pay the deposit");
+        assert_eq!(
+            Balances::reserved_balance(&alice),
+            deposit(1, 17) + deposit(0, size)
+        );
+
+        Identity::set_subs(
+            RuntimeOrigin::signed(alice.clone()),
+            vec![
+                (Charlie.to_account_id(), Data::None),
+                (Dave.to_account_id(), Data::None),
+            ],
+        )
+        .expect("Alice has an identity");
+        assert_eq!(
+            Balances::reserved_balance(&alice),
+            deposit(1, 17) + deposit(0, size) + 2 * deposit(1, 53)
+        );
+    });
+}
+
+#[test]
+fn registrars_are_added_by_governance() {
+    new_test_ext().execute_with(|| {
+        let eve = Eve.to_account_id();
+        assert_eq!(
+            Identity::add_registrar(RuntimeOrigin::signed(eve.clone()), eve.clone().into()),
+            Err(DispatchError::from(BadOrigin).into())
+        );
+        assert_eq!(
+            Identity::add_registrar(Origin::StakingAdmin.into(), eve.clone().into()),
+            Err(DispatchError::from(BadOrigin).into())
+        );
+        assert!(Identity::add_registrar(Origin::GeneralAdmin.into(), eve.into()).is_ok());
+        assert_eq!(Identity::registrars().len(), 1);
+    });
+}
+
+#[test]
+fn validators_are_listed_with_their_display_names() {
+    new_test_ext().execute_with(|| {
+        Identity::set_identity(
+            RuntimeOrigin::signed(Alice.to_account_id()),
+            identity(b"Alice's validator"),
+        )
+        .expect("Alice can pay the deposit");
+
+        // Collator selection sorts the invulnerables by account id.
+        assert_eq!(
+            staking_api::validators(),
+            vec![
+                staking_api::ValidatorInfo {
+                    account: Bob.to_account_id(),
+                    display: None,
+                },
+                staking_api::ValidatorInfo {
+                    account: Alice.to_account_id(),
+                    display: Some(b"Alice's validator".to_vec()),
+                },
+            ]
+        );
+    });
+}
+
+#[test]
+fn sub_identities_show_the_display_name_of_their_parent() {
+    new_test_ext().execute_with(|| {
+        let alice = Alice.to_account_id();
+        Identity::set_identity(
+            RuntimeOrigin::signed(alice.clone()),
+            identity(b"Alice's validators"),
+        )
+        .expect("Alice can pay the deposit");
+        Identity::set_subs(
+            RuntimeOrigin::signed(alice),
+            vec![(
+                Bob.to_account_id(),
+                Data::Raw(
+                    b"second"
+                        .to_vec()
+                        .try_into()
+                        .expect("the name is short enough"),
+                ),
+            )],
+        )
+        .expect("Alice has an identity");
+
+        assert_eq!(
+            staking_api::display_name(&Bob.to_account_id()),
+            Some(b"Alice's validators/second".to_vec())
+        );
+    });
+}
@@ -0,0 +1,134 @@
+//! The runtime in a network with the relay chain and a sibling parachain, simulated with
+//! `xcm-simulator`.
+//!
+//! The runtime receives messages through its XCMP queue and the message queue, like it does on a
+//! live network. The relay chain and the sibling are minimal mocks that execute messages right
+//! away.
+
+#![allow(dead_code)]
+
+pub mod relay_chain;
+pub mod sibling;
+
+use crate::common::new_test_ext;
+use cumulus_primitives_core::{AggregateMessageOrigin, DmpMessageHandler, XcmpMessageHandler};
+use frame_support::{
+    pallet_prelude::ValueQuery,
+    traits::{EnqueueMessage, ServiceQueues},
+};
+use parachain_template_runtime::{
+    genesis_config_presets::PARACHAIN_ID, MessageQueue, System, XcmpQueue,
+};
+use xcm_simulator::{
+    decl_test_network, decl_test_parachain, decl_test_relay_chain,
+    AggregateMessageOrigin as RelayMessageOrigin, ParaId, RelayBlockNumber, TestExt, UmpQueueId,
+    Weight,
+};
+
+/// The id of the sibling parachain.
+pub const SIBLING_ID: u32 = 2000;
+
+/// Hands messages to the runtime's queues and services them, like the next block would.
+pub struct RuntimeMessages;
+
+impl XcmpMessageHandler for RuntimeMessages {
+    fn handle_xcmp_messages<'a, I: Iterator<Item = (ParaId, RelayBlockNumber, &'a [u8])>>(
+        iter: I,
+        max_weight: Weight,
+    ) -> Weight {
+        let weight = <XcmpQueue as XcmpMessageHandler>::handle_xcmp_messages(iter, max_weight);
+        weight.saturating_add(<MessageQueue as ServiceQueues>::service_queues(max_weight))
+    }
+}
+
+impl DmpMessageHandler for RuntimeMessages {
+    fn handle_dmp_messages(
+        iter: impl Iterator<Item = (RelayBlockNumber, Vec<u8>)>,
+        max_weight: Weight,
+    ) -> Weight {
+        for (_sent_at, message) in iter {
+            <MessageQueue as EnqueueMessage<AggregateMessageOrigin>>::enqueue_message(
+                message
+                    .as_slice()
+                    .try_into()
+                    .expect("downward messages fit into the queue"),
+                AggregateMessageOrigin::Parent,
+            );
+        }
+        <MessageQueue as ServiceQueues>::service_queues(max_weight)
+    }
+}
+
+/// The upward messages the parachain system keeps for the next candidate. The storage item is
+/// private to the pallet.
+#[frame_support::storage_alias]
+type PendingUpwardMessages = StorageValue<ParachainSystem, Vec<Vec<u8>>, ValueQuery>;
+
+/// Delivers the messages the runtime sent to the relay chain.
+///
+/// The runtime sends upward messages through the parachain system, which keeps them for the next
+/// candidate rather than handing them to the simulator.
+pub fn deliver_upward_messages() {
+    let messages = TemplatePara::execute_with(PendingUpwardMessages::take);
+    Relay::execute_with(|| {
+        let origin = RelayMessageOrigin::Ump(UmpQueueId::Para(PARACHAIN_ID.into()));
+        for message in messages {
+            <relay_chain::MessageQueue as EnqueueMessage<RelayMessageOrigin>>::enqueue_message(
+                message
+                    .as_slice()
+                    .try_into()
+                    .expect("upward messages fit into the queue"),
+                origin.clone(),
+            );
+        }
+        <relay_chain::MessageQueue as ServiceQueues>::service_queues(Weight::MAX);
+    });
+}
+
+/// The genesis state of [`new_test_ext`], past the genesis block so events are recorded.
+pub fn runtime_ext() -> sp_io::TestExternalities {
+    let mut ext = new_test_ext();
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+decl_test_parachain! {
+    pub struct TemplatePara {
+        Runtime = parachain_template_runtime::Runtime,
+        XcmpMessageHandler = RuntimeMessages,
+        DmpMessageHandler = RuntimeMessages,
+        new_ext = runtime_ext(),
+    }
+}
+
+decl_test_parachain! {
+    pub struct SiblingPara {
+        Runtime = sibling::Runtime,
+        XcmpMessageHandler = sibling::ExecuteMessages,
+        DmpMessageHandler = sibling::ExecuteMessages,
+        new_ext = sibling::ext(),
+    }
+}
+
+decl_test_relay_chain! {
+    pub struct Relay {
+        Runtime = relay_chain::Runtime,
+        RuntimeCall = relay_chain::RuntimeCall,
+        RuntimeEvent = relay_chain::RuntimeEvent,
+        XcmConfig = relay_chain::XcmConfig,
+        MessageQueue = relay_chain::MessageQueue,
+        System = relay_chain::System,
+        new_ext = relay_chain::ext(),
+    }
+}
+
+// The runtime has the id of `genesis_config_presets::PARACHAIN_ID`.
+decl_test_network! {
+    pub struct Network {
+        relay_chain = Relay,
+        parachains = vec![
+            (1000, TemplatePara),
+            (2000, SiblingPara),
+        ],
+    }
+}
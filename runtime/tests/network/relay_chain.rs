@@ -0,0 +1,194 @@
+//! A minimal relay chain, with a native token and a message queue for upward messages.
+
+use frame_support::{
+    construct_runtime, derive_impl, parameter_types,
+    traits::{ConstU128, ConstU32, Everything, Nothing, ProcessMessage, ProcessMessageError},
+    weights::WeightMeter,
+};
+use frame_system::EnsureRoot;
+use parachain_template_runtime::{genesis_config_presets::PARACHAIN_ID, AccountId, Balance};
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::{traits::IdentityLookup, BuildStorage};
+use xcm::latest::prelude::*;
+use xcm_builder::{
+    AccountId32Aliases, AllowUnpaidExecutionFrom, ChildParachainConvertsVia, EnsureXcmOrigin,
+    FixedRateOfFungible, FixedWeightBounds, FrameTransactionalProcessor, FungibleAdapter,
+    IsConcrete, ProcessXcmMessage, SignedAccountId32AsNative, SignedToAccountId32,
+    SovereignSignedViaLocation,
+};
+use xcm_executor::XcmExecutor;
+use xcm_simulator::{AggregateMessageOrigin, ParaId, UmpQueueId, Weight};
+
+/// What each well-known account holds at genesis.
+pub const INITIAL_BALANCE: Balance = 1_000_000_000_000_000;
+
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type Block = Block;
+    type AccountData = pallet_balances::AccountData<Balance>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+}
+
+parameter_types! {
+    pub const TokenLocation: Location = Here.into_location();
+    pub const RelayNetwork: Option<NetworkId> = None;
+    pub UniversalLocation: InteriorLocation = Here;
+    pub const UnitWeightCost: Weight = Weight::from_parts(1_000, 1_000);
+    pub TokensPerSecondPerByte: (AssetId, u128, u128) = (AssetId(TokenLocation::get()), 1, 1);
+    pub const MaxInstructions: u32 = 100;
+    pub const MaxAssetsIntoHolding: u32 = 64;
+    pub TokenFromRuntime: (AssetFilter, Location) = (
+        Wild(AllOf { id: AssetId(TokenLocation::get()), fun: WildFungible }),
+        Parachain(PARACHAIN_ID).into(),
+    );
+}
+
+pub type LocationToAccountId = (
+    ChildParachainConvertsVia<ParaId, AccountId>,
+    AccountId32Aliases<RelayNetwork, AccountId>,
+);
+
+pub type XcmOriginToCallOrigin = (
+    SovereignSignedViaLocation<LocationToAccountId, RuntimeOrigin>,
+    SignedAccountId32AsNative<RelayNetwork, RuntimeOrigin>,
+);
+
+pub type LocalAssetTransactor =
+    FungibleAdapter<Balances, IsConcrete<TokenLocation>, LocationToAccountId, AccountId, ()>;
+
+/// With `relay-teleports`, the runtime is trusted to teleport the token like a system parachain.
+#[cfg(feature = "relay-teleports")]
+pub type TrustedTeleporters = xcm_builder::Case<TokenFromRuntime>;
+#[cfg(not(feature = "relay-teleports"))]
+pub type TrustedTeleporters = ();
+
+pub type XcmRouter = super::RelayChainXcmRouter;
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+    type RuntimeCall = RuntimeCall;
+    type XcmSender = XcmRouter;
+    type AssetTransactor = LocalAssetTransactor;
+    type OriginConverter = XcmOriginToCallOrigin;
+    type IsReserve = ();
+    type IsTeleporter = TrustedTeleporters;
+    type UniversalLocation = UniversalLocation;
+    type Barrier = AllowUnpaidExecutionFrom<Everything>;
+    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+    type Trader = FixedRateOfFungible<TokensPerSecondPerByte, ()>;
+    type ResponseHandler = ();
+    type AssetTrap = ();
+    type AssetLocker = ();
+    type AssetExchanger = ();
+    type AssetClaims = ();
+    type SubscriptionService = ();
+    type PalletInstancesInfo = ();
+    type FeeManager = ();
+    type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+    type MessageExporter = ();
+    type UniversalAliases = Nothing;
+    type CallDispatcher = RuntimeCall;
+    type SafeCallFilter = Everything;
+    type Aliasers = Nothing;
+    type TransactionalProcessor = FrameTransactionalProcessor;
+}
+
+pub type LocalOriginToLocation = SignedToAccountId32<RuntimeOrigin, AccountId, RelayNetwork>;
+
+impl pallet_xcm::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+    type XcmRouter = XcmRouter;
+    type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+    type XcmExecuteFilter = Everything;
+    type XcmExecutor = XcmExecutor<XcmConfig>;
+    type XcmTeleportFilter = Everything;
+    type XcmReserveTransferFilter = Everything;
+    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+    type UniversalLocation = UniversalLocation;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
+    type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+    type Currency = Balances;
+    type CurrencyMatcher = IsConcrete<TokenLocation>;
+    type TrustedLockers = ();
+    type SovereignAccountOf = LocationToAccountId;
+    type MaxLockers = ConstU32<8>;
+    type MaxRemoteLockConsumers = ConstU32<0>;
+    type RemoteLockConsumerIdentifier = ();
+    type WeightInfo = pallet_xcm::TestWeightInfo;
+    type AdminOrigin = EnsureRoot<AccountId>;
+}
+
+/// Executes upward messages as XCM from the parachain that sent them.
+pub struct MessageProcessor;
+impl ProcessMessage for MessageProcessor {
+    type Origin = AggregateMessageOrigin;
+
+    fn process_message(
+        message: &[u8],
+        origin: Self::Origin,
+        meter: &mut WeightMeter,
+        id: &mut [u8; 32],
+    ) -> Result<bool, ProcessMessageError> {
+        let AggregateMessageOrigin::Ump(UmpQueueId::Para(para)) = origin;
+        ProcessXcmMessage::<Junction, XcmExecutor<XcmConfig>, RuntimeCall>::process_message(
+            message,
+            Junction::Parachain(para.into()),
+            meter,
+            id,
+        )
+    }
+}
+
+impl pallet_message_queue::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type WeightInfo = ();
+    type MessageProcessor = MessageProcessor;
+    type Size = u32;
+    type QueueChangeHandler = ();
+    type QueuePausedQuery = ();
+    type HeapSize = ConstU32<{ 64 * 1024 }>;
+    type MaxStale = ConstU32<8>;
+    type ServiceWeight = ();
+}
+
+construct_runtime!(
+    pub enum Runtime {
+        System: frame_system = 0,
+        Balances: pallet_balances = 4,
+        // Indexed like on Rococo, which also keeps the origin of `XcmPallet` from taking the index
+        // of the `Void` origin `construct_runtime!` adds.
+        MessageQueue: pallet_message_queue = 64,
+        XcmPallet: pallet_xcm = 99,
+    }
+);
+
+/// The genesis state, with all well-known accounts endowed with [`INITIAL_BALANCE`].
+pub fn ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::<Runtime>::default()
+        .build_storage()
+        .expect("genesis storage builds");
+    pallet_balances::GenesisConfig::<Runtime> {
+        balances: Sr25519Keyring::iter()
+            .map(|keyring| (keyring.to_account_id(), INITIAL_BALANCE))
+            .collect(),
+    }
+    .assimilate_storage(&mut storage)
+    .expect("genesis storage builds");
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
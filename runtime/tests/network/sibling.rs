@@ -0,0 +1,203 @@
+//! A minimal sibling parachain, whose native token is transferred to the runtime.
+
+use codec::{Decode, Encode};
+use cumulus_primitives_core::{DmpMessageHandler, XcmpMessageFormat, XcmpMessageHandler};
+use frame_support::{
+    construct_runtime, derive_impl, parameter_types,
+    traits::{ConstU128, ConstU32, Everything, Nothing},
+};
+use frame_system::EnsureRoot;
+use parachain_template_runtime::{AccountId, Balance};
+use polkadot_parachain_primitives::primitives::Sibling;
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::{traits::IdentityLookup, BuildStorage};
+use xcm::{latest::prelude::*, VersionedXcm};
+use xcm_builder::{
+    AccountId32Aliases, AllowUnpaidExecutionFrom, EnsureXcmOrigin, FixedRateOfFungible,
+    FixedWeightBounds, FrameTransactionalProcessor, FungibleAdapter, IsConcrete, NativeAsset,
+    ParentIsPreset, SiblingParachainConvertsVia, SignedAccountId32AsNative, SignedToAccountId32,
+    SovereignSignedViaLocation,
+};
+use xcm_executor::XcmExecutor;
+use xcm_simulator::{ParaId, RelayBlockNumber, Weight};
+
+/// What each well-known account holds at genesis.
+pub const INITIAL_BALANCE: Balance = 1_000_000_000_000_000;
+
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<AccountId>;
+    type Block = Block;
+    type AccountData = pallet_balances::AccountData<Balance>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+}
+
+parameter_types! {
+    pub const SelfParaId: ParaId = ParaId::new(super::SIBLING_ID);
+    pub const TokenLocation: Location = Here.into_location();
+    pub const RelayNetwork: Option<NetworkId> = None;
+    pub UniversalLocation: InteriorLocation = Parachain(super::SIBLING_ID).into();
+    pub const UnitWeightCost: Weight = Weight::from_parts(1_000, 1_000);
+    pub TokensPerSecondPerByte: (AssetId, u128, u128) = (AssetId(TokenLocation::get()), 1, 1);
+    pub const MaxInstructions: u32 = 100;
+    pub const MaxAssetsIntoHolding: u32 = 64;
+}
+
+pub type LocationToAccountId = (
+    ParentIsPreset<AccountId>,
+    SiblingParachainConvertsVia<Sibling, AccountId>,
+    AccountId32Aliases<RelayNetwork, AccountId>,
+);
+
+pub type XcmOriginToCallOrigin = (
+    SovereignSignedViaLocation<LocationToAccountId, RuntimeOrigin>,
+    SignedAccountId32AsNative<RelayNetwork, RuntimeOrigin>,
+);
+
+pub type LocalAssetTransactor =
+    FungibleAdapter<Balances, IsConcrete<TokenLocation>, LocationToAccountId, AccountId, ()>;
+
+pub type XcmRouter = super::ParachainXcmRouter<SelfParaId>;
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+    type RuntimeCall = RuntimeCall;
+    type XcmSender = XcmRouter;
+    type AssetTransactor = LocalAssetTransactor;
+    type OriginConverter = XcmOriginToCallOrigin;
+    type IsReserve = NativeAsset;
+    type IsTeleporter = ();
+    type UniversalLocation = UniversalLocation;
+    type Barrier = AllowUnpaidExecutionFrom<Everything>;
+    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+    type Trader = FixedRateOfFungible<TokensPerSecondPerByte, ()>;
+    type ResponseHandler = ();
+    type AssetTrap = ();
+    type AssetLocker = ();
+    type AssetExchanger = ();
+    type AssetClaims = ();
+    type SubscriptionService = ();
+    type PalletInstancesInfo = ();
+    type FeeManager = ();
+    type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
+    type MessageExporter = ();
+    type UniversalAliases = Nothing;
+    type CallDispatcher = RuntimeCall;
+    type SafeCallFilter = Everything;
+    type Aliasers = Nothing;
+    type TransactionalProcessor = FrameTransactionalProcessor;
+}
+
+pub type LocalOriginToLocation = SignedToAccountId32<RuntimeOrigin, AccountId, RelayNetwork>;
+
+impl pallet_xcm::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+    type XcmRouter = XcmRouter;
+    type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+    type XcmExecuteFilter = Everything;
+    type XcmExecutor = XcmExecutor<XcmConfig>;
+    type XcmTeleportFilter = Nothing;
+    type XcmReserveTransferFilter = Everything;
+    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+    type UniversalLocation = UniversalLocation;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
+    type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+    type Currency = Balances;
+    type CurrencyMatcher = ();
+    type TrustedLockers = ();
+    type SovereignAccountOf = LocationToAccountId;
+    type MaxLockers = ConstU32<8>;
+    type MaxRemoteLockConsumers = ConstU32<0>;
+    type RemoteLockConsumerIdentifier = ();
+    type WeightInfo = pallet_xcm::TestWeightInfo;
+    type AdminOrigin = EnsureRoot<AccountId>;
+}
+
+construct_runtime!(
+    pub enum Runtime {
+        System: frame_system = 0,
+        Balances: pallet_balances = 10,
+        // Indexed like in the runtime, which also keeps the origin of `PolkadotXcm` from taking the
+        // index of the `Void` origin `construct_runtime!` adds.
+        PolkadotXcm: pallet_xcm = 31,
+    }
+);
+
+/// Executes `message` from `origin` right away.
+fn execute(origin: Location, message: VersionedXcm<RuntimeCall>, max_weight: Weight) {
+    let mut hash = message.using_encoded(sp_io::hashing::blake2_256);
+    let message = Xcm::<RuntimeCall>::try_from(message).expect("the message has a known version");
+    let _ = XcmExecutor::<XcmConfig>::prepare_and_execute(
+        origin,
+        message,
+        &mut hash,
+        max_weight,
+        Weight::zero(),
+    );
+}
+
+/// Executes messages as soon as they arrive, there is no queue.
+pub struct ExecuteMessages;
+
+impl XcmpMessageHandler for ExecuteMessages {
+    fn handle_xcmp_messages<'a, I: Iterator<Item = (ParaId, RelayBlockNumber, &'a [u8])>>(
+        iter: I,
+        max_weight: Weight,
+    ) -> Weight {
+        for (sender, _sent_at, mut data) in iter {
+            let (_format, message) =
+                <(XcmpMessageFormat, VersionedXcm<RuntimeCall>)>::decode(&mut data)
+                    .expect("the simulator sends one versioned message");
+            execute(
+                Location::new(1, [Parachain(sender.into())]),
+                message,
+                max_weight,
+            );
+        }
+        max_weight
+    }
+}
+
+impl DmpMessageHandler for ExecuteMessages {
+    fn handle_dmp_messages(
+        iter: impl Iterator<Item = (RelayBlockNumber, Vec<u8>)>,
+        max_weight: Weight,
+    ) -> Weight {
+        for (_sent_at, message) in iter {
+            let message = VersionedXcm::<RuntimeCall>::decode(&mut &message[..])
+                .expect("the simulator sends versioned messages");
+            execute(Location::parent(), message, max_weight);
+        }
+        max_weight
+    }
+}
+
+/// The genesis state, with all well-known accounts endowed with [`INITIAL_BALANCE`].
+pub fn ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::<Runtime>::default()
+        .build_storage()
+        .expect("genesis storage builds");
+    pallet_balances::GenesisConfig::<Runtime> {
+        balances: Sr25519Keyring::iter()
+            .map(|keyring| (keyring.to_account_id(), INITIAL_BALANCE))
+            .collect(),
+    }
+    .assimilate_storage(&mut storage)
+    .expect("genesis storage builds");
+
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,116 @@
+//! Proxies acting for accounts whose keys are kept cold.
+
+mod common;
+
+use common::{events, new_test_ext};
+use parachain_template_runtime::{
+    Balances, Proxy, ProxyType, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, System, UNIT,
+};
+use sp_keyring::Sr25519Keyring::{Alice, Dave};
+use sp_runtime::DispatchError;
+
+/// Let Dave act for Alice as a proxy of `proxy_type`.
+fn add_dave_as_proxy(proxy_type: ProxyType) {
+    Proxy::add_proxy(
+        RuntimeOrigin::signed(Alice.to_account_id()),
+        Dave.to_account_id().into(),
+        proxy_type,
+        0,
+    )
+    .expect("Alice can pay the proxy deposit");
+}
+
+/// Dispatch `call` for Alice through Dave and return its result.
+fn proxy_call(call: RuntimeCall) -> Result<(), DispatchError> {
+    Proxy::proxy(
+        RuntimeOrigin::signed(Dave.to_account_id()),
+        Alice.to_account_id().into(),
+        None,
+        Box::new(call),
+    )
+    .expect("Dave is a proxy of Alice");
+    events()
+        .into_iter()
+        .rev()
+        .find_map(|event| match event {
+            RuntimeEvent::Proxy(pallet_proxy::Event::ProxyExecuted { result }) => Some(result),
+            _ => None,
+        })
+        .expect("the proxied call was executed")
+}
+
+fn transfer_to_dave() -> RuntimeCall {
+    RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+        dest: Dave.to_account_id().into(),
+        value: UNIT,
+    })
+}
+
+#[test]
+fn staking_proxy_manages_session_keys_but_cannot_transfer() {
+    new_test_ext().execute_with(|| {
+        // Events are not recorded in the genesis block.
+        System::set_block_number(1);
+        add_dave_as_proxy(ProxyType::Staking);
+        let alice = Alice.to_account_id();
+        let alice_balance = Balances::free_balance(&alice);
+
+        assert_eq!(
+            proxy_call(transfer_to_dave()),
+            Err(frame_system::Error::<Runtime>::CallFiltered.into())
+        );
+        // Batching doesn't get around the filter, it applies to every call of the batch.
+        let batch = RuntimeCall::Utility(pallet_utility::Call::batch_all {
+            calls: vec![transfer_to_dave()],
+        });
+        assert_eq!(
+            proxy_call(batch),
+            Err(frame_system::Error::<Runtime>::CallFiltered.into())
+        );
+        assert_eq!(Balances::free_balance(&alice), alice_balance);
+
+        let purge_keys = RuntimeCall::Session(pallet_session::Call::purge_keys {});
+        assert_eq!(proxy_call(purge_keys), Ok(()));
+        assert!(pallet_session::NextKeys::<Runtime>::get(&alice).is_none());
+    });
+}
+
+#[test]
+fn governance_proxy_cannot_touch_staking() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        add_dave_as_proxy(ProxyType::Governance);
+
+        let purge_keys = RuntimeCall::Session(pallet_session::Call::purge_keys {});
+        assert_eq!(
+            proxy_call(purge_keys),
+            Err(frame_system::Error::<Runtime>::CallFiltered.into())
+        );
+        assert!(pallet_session::NextKeys::<Runtime>::get(Alice.to_account_id()).is_some());
+    });
+}
+
+#[test]
+fn proxies_cannot_add_proxies_with_more_rights() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        add_dave_as_proxy(ProxyType::NonTransfer);
+
+        let add_any = RuntimeCall::Proxy(pallet_proxy::Call::add_proxy {
+            delegate: Dave.to_account_id().into(),
+            proxy_type: ProxyType::Any,
+            delay: 0,
+        });
+        assert_eq!(
+            proxy_call(add_any),
+            Err(frame_system::Error::<Runtime>::CallFiltered.into())
+        );
+
+        let add_staking = RuntimeCall::Proxy(pallet_proxy::Call::add_proxy {
+            delegate: Dave.to_account_id().into(),
+            proxy_type: ProxyType::Staking,
+            delay: 0,
+        });
+        assert_eq!(proxy_call(add_staking), Ok(()));
+    });
+}
@@ -0,0 +1,159 @@
+//! Accounts on a sibling parachain staking on the runtime with `Transact`, without keys on it.
+//!
+//! Staking on the runtime is collator selection: a remote account sets its session keys and
+//! registers as a candidate. Bonding, nominating and claiming rewards come with the staking and
+//! nomination pools pallets.
+
+mod common;
+mod network;
+
+use codec::Encode;
+use common::events;
+use frame_support::{dispatch::GetDispatchInfo, traits::ValidatorRegistration};
+use network::{sibling, Network, SiblingPara, TemplatePara, SIBLING_ID};
+use parachain_template_runtime::{
+    genesis_config_presets::{session_keys, PARACHAIN_ID},
+    xcm_config::LocationToAccountId,
+    AccountId, Balances, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, Session, UNIT,
+};
+use sp_keyring::{
+    Ed25519Keyring,
+    Sr25519Keyring::{self, Alice, Charlie, Eve},
+};
+use xcm::{latest::prelude::*, VersionedLocation, VersionedXcm};
+use xcm_executor::traits::ConvertLocation;
+use xcm_simulator::TestExt;
+
+/// What the runtime holds for the execution fees of each message, in its native token.
+const FEE: u128 = UNIT / 10;
+
+/// The account of Alice on the sibling, as the runtime sees it.
+fn remote_alice() -> AccountId {
+    let location = Location::new(
+        1,
+        [
+            Parachain(SIBLING_ID),
+            AccountId32 {
+                network: None,
+                id: Alice.to_raw_public(),
+            },
+        ],
+    );
+    LocationToAccountId::convert_location(&location).expect("remote accounts have an account")
+}
+
+/// Funds Alice's remote account on the runtime, so it can pay for execution and its bond.
+fn fund_remote_alice() {
+    TemplatePara::execute_with(|| {
+        Balances::transfer_keep_alive(
+            RuntimeOrigin::signed(Alice.to_account_id()),
+            remote_alice().into(),
+            UNIT,
+        )
+        .expect("Alice can afford the transfer");
+    });
+}
+
+/// Alice sends `call` from the sibling, to be dispatched by her remote account.
+fn transact_from_sibling(call: RuntimeCall) {
+    let fee: Asset = (Here, FEE).into();
+    let message = Xcm(vec![
+        WithdrawAsset(fee.clone().into()),
+        BuyExecution {
+            fees: fee,
+            weight_limit: Unlimited,
+        },
+        Transact {
+            origin_kind: OriginKind::SovereignAccount,
+            require_weight_at_most: call.get_dispatch_info().weight,
+            call: call.encode().into(),
+        },
+        RefundSurplus,
+        DepositAsset {
+            assets: Wild(AllCounted(1)),
+            beneficiary: Location::new(
+                1,
+                [
+                    Parachain(SIBLING_ID),
+                    AccountId32 {
+                        network: None,
+                        id: Alice.to_raw_public(),
+                    },
+                ],
+            ),
+        },
+    ]);
+    SiblingPara::execute_with(|| {
+        sibling::PolkadotXcm::send(
+            sibling::RuntimeOrigin::signed(Alice.to_account_id()),
+            Box::new(VersionedLocation::V4(Location::new(
+                1,
+                [Parachain(PARACHAIN_ID)],
+            ))),
+            Box::new(VersionedXcm::V4(message)),
+        )
+        .expect("the sibling routes messages to the runtime");
+    });
+}
+
+/// Sets `keyring`'s session keys.
+fn set_keys(keyring: Sr25519Keyring, grandpa: Ed25519Keyring) -> RuntimeCall {
+    RuntimeCall::Session(pallet_session::Call::set_keys {
+        keys: session_keys(keyring.public().into(), grandpa.public().into()),
+        proof: vec![],
+    })
+}
+
+#[test]
+fn remote_account_registers_as_collator_candidate() {
+    Network::reset();
+    fund_remote_alice();
+
+    transact_from_sibling(RuntimeCall::Utility(pallet_utility::Call::batch_all {
+        calls: vec![
+            set_keys(Charlie, Ed25519Keyring::Charlie),
+            RuntimeCall::CollatorSelection(
+                pallet_collator_selection::Call::register_as_candidate {},
+            ),
+        ],
+    }));
+
+    TemplatePara::execute_with(|| {
+        let remote = remote_alice();
+        assert!(Session::is_registered(&remote));
+        assert!(pallet_collator_selection::CandidateList::<Runtime>::get()
+            .iter()
+            .any(|candidate| candidate.who == remote));
+        assert!(Balances::reserved_balance(&remote) > 0);
+    });
+}
+
+#[test]
+fn transact_only_dispatches_staking_calls() {
+    Network::reset();
+    fund_remote_alice();
+    let eve_balance = TemplatePara::execute_with(|| Balances::free_balance(Eve.to_account_id()));
+
+    // A batch is filtered as a whole when any call in it is not a staking call.
+    transact_from_sibling(RuntimeCall::Utility(pallet_utility::Call::batch_all {
+        calls: vec![
+            set_keys(Charlie, Ed25519Keyring::Charlie),
+            RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+                dest: Eve.to_account_id().into(),
+                value: UNIT / 2,
+            }),
+        ],
+    }));
+
+    TemplatePara::execute_with(|| {
+        assert!(!Session::is_registered(&remote_alice()));
+        assert_eq!(Balances::free_balance(Eve.to_account_id()), eve_balance);
+        assert!(events().iter().any(|event| matches!(
+            event,
+            RuntimeEvent::MessageQueue(pallet_message_queue::Event::Processed {
+                success: false,
+                ..
+            })
+        )));
+    });
+}
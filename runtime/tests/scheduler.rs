@@ -0,0 +1,126 @@
+//! Delayed dispatch through the scheduler, with calls kept as preimages.
+
+mod common;
+
+use codec::Encode;
+use common::{new_test_ext, signed_extrinsic, Chain};
+use frame_support::traits::{
+    fungible::InspectHold, schedule::DispatchTime, Bounded, QueryPreimage,
+};
+use parachain_template_runtime::{
+    governance::pallet_custom_origins::Origin, Balances, OriginCaller, Preimage,
+    PreimageBaseDeposit, PreimageByteDeposit, PreimageHoldReason, Runtime, RuntimeCall,
+    RuntimeOrigin, Scheduler,
+};
+use sp_keyring::Sr25519Keyring::{Alice, Bob};
+use sp_runtime::{
+    traits::{BadOrigin, BlakeTwo256, Hash},
+    DispatchError,
+};
+
+/// The slot the first block is authored in.
+const GENESIS_SLOT: u64 = 100;
+
+fn remark() -> Box<RuntimeCall> {
+    Box::new(RuntimeCall::System(frame_system::Call::remark {
+        remark: vec![],
+    }))
+}
+
+#[test]
+fn admin_tracks_cancel_only_what_they_scheduled() {
+    new_test_ext().execute_with(|| {
+        let task = [1; 32];
+        assert_eq!(
+            Scheduler::schedule_named(
+                RuntimeOrigin::signed(Alice.to_account_id()),
+                task,
+                10,
+                None,
+                0,
+                remark()
+            ),
+            Err(DispatchError::from(BadOrigin))
+        );
+        Scheduler::schedule_named(Origin::StakingAdmin.into(), task, 10, None, 0, remark())
+            .expect("the staking admin may schedule calls");
+
+        assert_eq!(
+            Scheduler::cancel_named(Origin::GeneralAdmin.into(), task),
+            Err(DispatchError::from(BadOrigin))
+        );
+        assert!(Scheduler::cancel_named(Origin::StakingAdmin.into(), task).is_ok());
+
+        // Root outranks the admin tracks.
+        Scheduler::schedule_named(Origin::GeneralAdmin.into(), task, 10, None, 0, remark())
+            .expect("the general admin may schedule calls");
+        assert!(Scheduler::cancel_named(RuntimeOrigin::root(), task).is_ok());
+    });
+}
+
+#[test]
+fn scheduled_calls_are_dispatched_with_the_scheduling_origin() {
+    new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+        chain.author_block(GENESIS_SLOT, Alice, vec![]);
+
+        let call = RuntimeCall::CollatorSelection(
+            pallet_collator_selection::Call::set_desired_candidates { max: 7 },
+        );
+        Scheduler::schedule(Origin::StakingAdmin.into(), 3, None, 0, Box::new(call))
+            .expect("the staking admin may schedule calls");
+
+        chain.author_block(GENESIS_SLOT + 1, Bob, vec![]);
+        assert_ne!(
+            pallet_collator_selection::DesiredCandidates::<Runtime>::get(),
+            7
+        );
+        chain.author_block(GENESIS_SLOT + 2, Alice, vec![]);
+        assert_eq!(
+            pallet_collator_selection::DesiredCandidates::<Runtime>::get(),
+            7
+        );
+    });
+}
+
+#[test]
+fn runtime_upgrade_is_submitted_as_a_preimage() {
+    new_test_ext().execute_with(|| {
+        let mut chain = Chain::default();
+        chain.author_block(GENESIS_SLOT, Alice, vec![]);
+
+        // About the size of a compressed runtime.
+        let upgrade = RuntimeCall::System(frame_system::Call::set_code {
+            code: vec![0; 2 * 1024 * 1024],
+        });
+        let preimage = upgrade.encode();
+        let hash = BlakeTwo256::hash(&preimage);
+        let len = preimage.len() as u32;
+
+        let note = signed_extrinsic(
+            Alice,
+            RuntimeCall::Preimage(pallet_preimage::Call::note_preimage { bytes: preimage }),
+        );
+        chain.author_block(GENESIS_SLOT + 1, Bob, vec![note]);
+        assert_eq!(Preimage::len(&hash), Some(len));
+        assert_eq!(
+            Balances::balance_on_hold(&PreimageHoldReason::get(), &Alice.to_account_id()),
+            PreimageBaseDeposit::get() + PreimageByteDeposit::get() * u128::from(len)
+        );
+
+        // The referendum only refers to the upgrade by its hash.
+        let proposal = Bounded::Lookup { hash, len };
+        let submit = RuntimeCall::Referenda(pallet_referenda::Call::submit {
+            proposal_origin: Box::new(OriginCaller::system(frame_system::RawOrigin::Root)),
+            proposal: proposal.clone(),
+            enactment_moment: DispatchTime::After(0),
+        });
+        assert!(submit.encoded_size() < 128);
+        chain.author_block(GENESIS_SLOT + 2, Alice, vec![signed_extrinsic(Bob, submit)]);
+
+        assert_eq!(
+            Preimage::peek::<RuntimeCall>(&proposal).map(|(call, _)| call),
+            Ok(upgrade)
+        );
+    });
+}
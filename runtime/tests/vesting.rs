@@ -0,0 +1,167 @@
+//! Vesting schedules set up in genesis and created with vested transfers.
+
+mod common;
+
+use common::{new_test_ext, FERDIE_VESTING_BLOCKS};
+use frame_support::traits::{schedule::DispatchTime, StorePreimage, VestingSchedule};
+use pallet_conviction_voting::{AccountVote, Conviction, Vote};
+use pallet_vesting::VestingInfo;
+use parachain_template_runtime::{
+    genesis_config_presets::{session_keys, ENDOWMENT},
+    Balances, CollatorSelection, ConvictionVoting, OriginCaller, Preimage, Referenda, Runtime,
+    RuntimeCall, RuntimeOrigin, Session, System, Vesting, UNIT,
+};
+use sp_keyring::{
+    Ed25519Keyring,
+    Sr25519Keyring::{Alice, Dave, Ferdie},
+};
+use sp_runtime::TokenError;
+
+/// The part of Ferdie's endowment that is locked at genesis.
+const FERDIE_LOCKED: u128 = ENDOWMENT - ENDOWMENT / 10;
+
+#[test]
+fn genesis_schedule_unlocks_over_time() {
+    new_test_ext().execute_with(|| {
+        let ferdie = Ferdie.to_account_id();
+        assert_eq!(Vesting::vesting_balance(&ferdie), Some(FERDIE_LOCKED));
+
+        // Only the liquid part can be transferred.
+        assert!(Balances::transfer_keep_alive(
+            RuntimeOrigin::signed(ferdie.clone()),
+            Alice.to_account_id().into(),
+            ENDOWMENT / 10 + UNIT,
+        )
+        .is_err());
+
+        let per_block = FERDIE_LOCKED / u128::from(FERDIE_VESTING_BLOCKS);
+        System::set_block_number(FERDIE_VESTING_BLOCKS / 2);
+        Vesting::vest(RuntimeOrigin::signed(ferdie.clone())).expect("Ferdie has a schedule");
+        assert_eq!(
+            Vesting::vesting_balance(&ferdie),
+            Some(FERDIE_LOCKED - per_block * u128::from(FERDIE_VESTING_BLOCKS / 2))
+        );
+
+        // The remainder of dividing the locked funds by the length unlocks one block later.
+        System::set_block_number(FERDIE_VESTING_BLOCKS + 1);
+        Vesting::vest(RuntimeOrigin::signed(ferdie.clone())).expect("Ferdie has a schedule");
+        assert_eq!(Vesting::vesting_balance(&ferdie), None);
+        assert!(Balances::transfer_keep_alive(
+            RuntimeOrigin::signed(ferdie),
+            Alice.to_account_id().into(),
+            ENDOWMENT / 2,
+        )
+        .is_ok());
+    });
+}
+
+#[test]
+fn vested_transfer_locks_the_transferred_funds() {
+    new_test_ext().execute_with(|| {
+        let alice = Alice.to_account_id();
+        let dave = Dave.to_account_id();
+        let dave_balance = Balances::free_balance(&dave);
+
+        assert_eq!(
+            Vesting::vested_transfer(
+                RuntimeOrigin::signed(alice.clone()),
+                dave.clone().into(),
+                VestingInfo::new(UNIT / 2, UNIT / 20, 1),
+            ),
+            Err(pallet_vesting::Error::<Runtime>::AmountLow.into())
+        );
+
+        let schedule = VestingInfo::new(100 * UNIT, UNIT, 10);
+        Vesting::vested_transfer(RuntimeOrigin::signed(alice), dave.clone().into(), schedule)
+            .expect("Alice can afford the transfer");
+        assert_eq!(Balances::free_balance(&dave), dave_balance + 100 * UNIT);
+        assert_eq!(Vesting::vesting_balance(&dave), Some(100 * UNIT));
+        assert_eq!(
+            pallet_vesting::Vesting::<Runtime>::get(&dave).map(|schedules| schedules.into_inner()),
+            Some(vec![schedule])
+        );
+
+        // Dave can spend the previous balance, but not what was vested.
+        Balances::transfer_all(
+            RuntimeOrigin::signed(dave.clone()),
+            Alice.to_account_id().into(),
+            false,
+        )
+        .expect("the previous balance is not locked");
+        assert_eq!(Balances::free_balance(&dave), 100 * UNIT);
+        assert_eq!(
+            Balances::transfer_keep_alive(
+                RuntimeOrigin::signed(dave),
+                Alice.to_account_id().into(),
+                UNIT,
+            ),
+            Err(TokenError::Frozen.into())
+        );
+    });
+}
+
+#[test]
+fn unvested_funds_can_be_locked_for_voting() {
+    new_test_ext().execute_with(|| {
+        let ferdie = Ferdie.to_account_id();
+        let call = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+        let proposal = Preimage::bound(call).expect("the call is small enough to be bounded");
+        Referenda::submit(
+            RuntimeOrigin::signed(Alice.to_account_id()),
+            Box::new(OriginCaller::system(frame_system::RawOrigin::Root)),
+            proposal,
+            DispatchTime::After(0),
+        )
+        .expect("anyone can submit a referendum");
+        let index = pallet_referenda::ReferendumCount::<Runtime>::get() - 1;
+
+        // Voting locks the balance, the lock may cover funds that are still vesting.
+        let vote = AccountVote::Standard {
+            vote: Vote {
+                aye: true,
+                conviction: Conviction::Locked6x,
+            },
+            balance: Balances::free_balance(&ferdie),
+        };
+        ConvictionVoting::vote(RuntimeOrigin::signed(ferdie.clone()), index, vote)
+            .expect("Ferdie can vote with the whole balance");
+        assert_eq!(Vesting::vesting_balance(&ferdie), Some(FERDIE_LOCKED));
+        assert_eq!(
+            pallet_balances::Locks::<Runtime>::get(&ferdie).len(),
+            2,
+            "the vesting and the voting lock overlap"
+        );
+    });
+}
+
+#[test]
+fn unvested_funds_cannot_pay_the_candidacy_bond() {
+    new_test_ext().execute_with(|| {
+        let ferdie = Ferdie.to_account_id();
+        // Ferdie spends the liquid part, only funds that are still vesting are left.
+        Balances::transfer_all(
+            RuntimeOrigin::signed(ferdie.clone()),
+            Alice.to_account_id().into(),
+            false,
+        )
+        .expect("the liquid balance is not locked");
+        assert_eq!(Balances::free_balance(&ferdie), FERDIE_LOCKED);
+
+        Session::set_keys(
+            RuntimeOrigin::signed(ferdie.clone()),
+            session_keys(
+                Ferdie.public().into(),
+                Ed25519Keyring::Ferdie.public().into(),
+            ),
+            vec![],
+        )
+        .expect("Ferdie's keys are not taken");
+
+        // The candidacy bond is reserved, and locked funds can't be reserved.
+        assert_eq!(
+            CollatorSelection::register_as_candidate(RuntimeOrigin::signed(ferdie.clone())),
+            Err(pallet_balances::Error::<Runtime>::LiquidityRestrictions.into())
+        );
+        assert_eq!(Balances::reserved_balance(&ferdie), 0);
+    });
+}
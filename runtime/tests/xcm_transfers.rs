@@ -0,0 +1,364 @@
+//! Assets transferred between the runtime, the relay chain and a sibling parachain over XCM, in a
+//! network simulated with `xcm-simulator`.
+
+mod common;
+mod network;
+
+use common::{events, signed_extrinsic_paying_in, Chain};
+use network::{relay_chain, sibling, Network, Relay, SiblingPara, TemplatePara, SIBLING_ID};
+use parachain_template_runtime::{
+    genesis_config_presets::PARACHAIN_ID, governance::pallet_custom_origins::Origin, Balances,
+    ForeignAssets, PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, Treasury,
+    EXISTENTIAL_DEPOSIT,
+};
+use sp_keyring::Sr25519Keyring::{Alice, Bob, Eve};
+use sp_runtime::DispatchResult;
+use xcm::{latest::prelude::*, v3, VersionedAssets, VersionedLocation};
+use xcm_executor::traits::ConvertLocation;
+use xcm_simulator::TestExt;
+
+/// The amount Alice transfers to Eve.
+const AMOUNT: u128 = 1_000_000_000_000;
+
+/// The slot the first block is authored in.
+const GENESIS_SLOT: u64 = 100;
+
+/// The sibling's native token, as the runtime identifies it.
+fn sibling_token() -> v3::Location {
+    v3::Location::new(1, v3::Junctions::X1(v3::Junction::Parachain(SIBLING_ID)))
+}
+
+/// Eve's account, as the beneficiary of a transfer.
+fn eve() -> Box<VersionedLocation> {
+    Box::new(VersionedLocation::V4(
+        AccountId32 {
+            network: None,
+            id: Eve.to_raw_public(),
+        }
+        .into(),
+    ))
+}
+
+/// Registers `asset` as a sufficient foreign asset, owned by Alice so the tests can mint it.
+fn register_foreign_asset(asset: v3::Location) {
+    TemplatePara::execute_with(|| {
+        ForeignAssets::force_create(
+            Origin::GeneralAdmin.into(),
+            asset,
+            Alice.to_account_id().into(),
+            true,
+            EXISTENTIAL_DEPOSIT,
+        )
+        .expect("the general admin registers foreign assets");
+    });
+}
+
+/// Alice transfers [`AMOUNT`] of the sibling's native token to Eve on the runtime, with the sibling
+/// as the reserve.
+fn transfer_from_sibling_to_eve() {
+    SiblingPara::execute_with(|| {
+        sibling::PolkadotXcm::limited_reserve_transfer_assets(
+            sibling::RuntimeOrigin::signed(Alice.to_account_id()),
+            Box::new(VersionedLocation::V4(Location::new(
+                1,
+                [Parachain(PARACHAIN_ID)],
+            ))),
+            eve(),
+            Box::new(VersionedAssets::V4((Here, AMOUNT).into())),
+            0,
+            WeightLimit::Unlimited,
+        )
+        .expect("Alice can afford the transfer");
+    });
+}
+
+#[test]
+fn sibling_token_arrives_in_the_beneficiary_account() {
+    Network::reset();
+    register_foreign_asset(sibling_token());
+
+    transfer_from_sibling_to_eve();
+
+    SiblingPara::execute_with(|| {
+        let sovereign = sibling::LocationToAccountId::convert_location(&Location::new(
+            1,
+            [Parachain(PARACHAIN_ID)],
+        ))
+        .expect("siblings have sovereign accounts");
+        assert_eq!(sibling::Balances::free_balance(sovereign), AMOUNT);
+    });
+    TemplatePara::execute_with(|| {
+        let received = ForeignAssets::balance(sibling_token(), Eve.to_account_id());
+        let fee = ForeignAssets::balance(sibling_token(), Treasury::account_id());
+        assert!(fee > 0, "execution is paid in the sibling token");
+        assert_eq!(received + fee, AMOUNT);
+    });
+}
+
+#[test]
+fn unregistered_sibling_token_is_trapped() {
+    Network::reset();
+
+    transfer_from_sibling_to_eve();
+
+    TemplatePara::execute_with(|| {
+        assert_eq!(
+            ForeignAssets::balance(sibling_token(), Eve.to_account_id()),
+            0
+        );
+        assert!(events().iter().any(|event| matches!(
+            event,
+            RuntimeEvent::PolkadotXcm(pallet_xcm::Event::AssetsTrapped { .. })
+        )));
+    });
+}
+
+/// Alice transfers `amount` of the relay chain token to Eve on the runtime.
+fn transfer_from_relay_to_eve(amount: u128) {
+    Relay::execute_with(|| {
+        relay_chain::XcmPallet::transfer_assets(
+            relay_chain::RuntimeOrigin::signed(Alice.to_account_id()),
+            Box::new(VersionedLocation::V4(Parachain(PARACHAIN_ID).into())),
+            eve(),
+            Box::new(VersionedAssets::V4((Here, amount).into())),
+            0,
+            WeightLimit::Unlimited,
+        )
+        .expect("Alice can afford the transfer");
+    });
+}
+
+/// Eve transfers `asset` from the runtime to her account on the relay chain.
+fn transfer_from_eve_to_relay(asset: Asset) -> DispatchResult {
+    TemplatePara::execute_with(|| {
+        PolkadotXcm::transfer_assets(
+            RuntimeOrigin::signed(Eve.to_account_id()),
+            Box::new(VersionedLocation::V4(Location::parent())),
+            eve(),
+            Box::new(VersionedAssets::V4(asset.into())),
+            0,
+            WeightLimit::Unlimited,
+        )
+    })
+}
+
+/// Eve's balance of the relay chain token on the runtime.
+fn relay_token_of_eve() -> u128 {
+    TemplatePara::execute_with(|| {
+        ForeignAssets::balance(v3::Location::parent(), Eve.to_account_id())
+    })
+}
+
+/// Eve's balance on the relay chain.
+fn eve_on_the_relay_chain() -> u128 {
+    Relay::execute_with(|| relay_chain::Balances::free_balance(Eve.to_account_id()))
+}
+
+/// The balance of the runtime's sovereign account on the relay chain.
+#[cfg(not(feature = "relay-teleports"))]
+fn sovereign_on_the_relay_chain() -> u128 {
+    Relay::execute_with(|| {
+        let sovereign =
+            relay_chain::LocationToAccountId::convert_location(&Parachain(PARACHAIN_ID).into())
+                .expect("parachains have sovereign accounts");
+        relay_chain::Balances::free_balance(sovereign)
+    })
+}
+
+/// Asserts that Alice paid `amount` on the relay chain, and that Eve received it on the runtime
+/// less the execution fee, which went to the treasury. Returns what Eve received.
+fn assert_relay_token_arrived(amount: u128) -> u128 {
+    Relay::execute_with(|| {
+        assert_eq!(
+            relay_chain::Balances::free_balance(Alice.to_account_id()),
+            relay_chain::INITIAL_BALANCE - amount
+        );
+    });
+    TemplatePara::execute_with(|| {
+        let received = ForeignAssets::balance(v3::Location::parent(), Eve.to_account_id());
+        let fee = ForeignAssets::balance(v3::Location::parent(), Treasury::account_id());
+        assert!(fee > 0, "execution is paid in the relay chain token");
+        assert_eq!(received + fee, amount);
+        received
+    })
+}
+
+#[test]
+fn unregistered_relay_token_is_trapped() {
+    Network::reset();
+
+    transfer_from_relay_to_eve(AMOUNT);
+
+    assert_eq!(relay_token_of_eve(), 0);
+    TemplatePara::execute_with(|| {
+        assert!(events().iter().any(|event| matches!(
+            event,
+            RuntimeEvent::PolkadotXcm(pallet_xcm::Event::AssetsTrapped { .. })
+        )));
+    });
+}
+
+#[test]
+fn native_token_cannot_be_transferred_to_the_relay_chain() {
+    Network::reset();
+    let eve_balance = TemplatePara::execute_with(|| Balances::free_balance(Eve.to_account_id()));
+    let eve_relay_balance = eve_on_the_relay_chain();
+
+    // The native token is neither teleported to the relay chain, nor does the relay chain take
+    // reserve transfers.
+    assert_eq!(
+        transfer_from_eve_to_relay((Here, AMOUNT).into()),
+        Err(pallet_xcm::Error::<Runtime>::LocalExecutionIncomplete.into())
+    );
+    network::deliver_upward_messages();
+
+    TemplatePara::execute_with(|| {
+        assert_eq!(Balances::free_balance(Eve.to_account_id()), eve_balance);
+    });
+    assert_eq!(eve_on_the_relay_chain(), eve_relay_balance);
+}
+
+#[test]
+fn relay_token_that_arrived_pays_transaction_fees() {
+    Network::reset();
+    register_foreign_asset(v3::Location::parent());
+    transfer_from_relay_to_eve(AMOUNT);
+    let received = assert_relay_token_arrived(AMOUNT);
+
+    TemplatePara::execute_with(|| {
+        let mut chain = Chain::default();
+        chain.author_block(GENESIS_SLOT, Alice, vec![]);
+
+        // Eve has nothing but the relay chain token that arrived.
+        let eve = Eve.to_account_id();
+        Balances::transfer_all(
+            RuntimeOrigin::signed(eve.clone()),
+            Alice.to_account_id().into(),
+            false,
+        )
+        .expect("Eve's balance is free");
+        assert_eq!(Balances::free_balance(&eve), 0);
+
+        let remark = signed_extrinsic_paying_in(
+            Eve,
+            RuntimeCall::System(frame_system::Call::remark {
+                remark: vec![0; 32],
+            }),
+            Some(v3::Location::parent()),
+        );
+        chain.author_block(GENESIS_SLOT + 1, Bob, vec![remark]);
+
+        let fee = events()
+            .into_iter()
+            .find_map(|event| match event {
+                RuntimeEvent::AssetTxPayment(pallet_asset_tx_payment::Event::AssetTxFeePaid {
+                    who,
+                    actual_fee,
+                    ..
+                }) if who == eve => Some(actual_fee),
+                _ => None,
+            })
+            .expect("the remark paid a fee");
+        assert!(fee > 0);
+        assert_eq!(
+            ForeignAssets::balance(v3::Location::parent(), &eve),
+            received - fee
+        );
+    });
+}
+
+#[cfg(not(feature = "relay-teleports"))]
+#[test]
+fn relay_token_arrives_with_the_relay_chain_as_reserve() {
+    Network::reset();
+    register_foreign_asset(v3::Location::parent());
+    let eve_balance = TemplatePara::execute_with(|| Balances::free_balance(Eve.to_account_id()));
+
+    transfer_from_relay_to_eve(AMOUNT);
+
+    assert_eq!(sovereign_on_the_relay_chain(), AMOUNT);
+    assert_relay_token_arrived(AMOUNT);
+    TemplatePara::execute_with(|| {
+        assert_eq!(Balances::free_balance(Eve.to_account_id()), eve_balance);
+    });
+}
+
+#[cfg(not(feature = "relay-teleports"))]
+#[test]
+fn relay_token_round_trips_with_the_relay_chain_as_reserve() {
+    Network::reset();
+    register_foreign_asset(v3::Location::parent());
+    transfer_from_relay_to_eve(AMOUNT);
+    let received = assert_relay_token_arrived(AMOUNT);
+
+    transfer_from_eve_to_relay((Parent, received).into())
+        .expect("the relay chain token goes back to its reserve");
+    network::deliver_upward_messages();
+
+    assert_eq!(relay_token_of_eve(), 0);
+    assert_eq!(sovereign_on_the_relay_chain(), AMOUNT - received);
+    assert_eq!(
+        eve_on_the_relay_chain(),
+        relay_chain::INITIAL_BALANCE + received
+    );
+}
+
+/// The relay chain token that arrived by teleport and hasn't left again, as the checking account
+/// tracks it.
+#[cfg(feature = "relay-teleports")]
+fn teleported_in() -> u128 {
+    TemplatePara::execute_with(|| {
+        ForeignAssets::balance(
+            v3::Location::parent(),
+            parachain_template_runtime::xcm_config::CheckingAccount::get(),
+        )
+    })
+}
+
+#[cfg(feature = "relay-teleports")]
+#[test]
+fn relay_token_round_trips_by_teleport() {
+    Network::reset();
+    register_foreign_asset(v3::Location::parent());
+
+    transfer_from_relay_to_eve(AMOUNT);
+    let received = assert_relay_token_arrived(AMOUNT);
+    assert_eq!(teleported_in(), AMOUNT);
+
+    transfer_from_eve_to_relay((Parent, received).into())
+        .expect("the relay chain token is teleported");
+    network::deliver_upward_messages();
+
+    assert_eq!(relay_token_of_eve(), 0);
+    assert_eq!(teleported_in(), AMOUNT - received);
+    assert_eq!(
+        eve_on_the_relay_chain(),
+        relay_chain::INITIAL_BALANCE + received
+    );
+}
+
+#[cfg(feature = "relay-teleports")]
+#[test]
+fn no_more_relay_token_is_teleported_out_than_arrived() {
+    Network::reset();
+    register_foreign_asset(v3::Location::parent());
+    // Eve holds relay chain tokens that never arrived by teleport.
+    TemplatePara::execute_with(|| {
+        ForeignAssets::mint(
+            RuntimeOrigin::signed(Alice.to_account_id()),
+            v3::Location::parent(),
+            Eve.to_account_id().into(),
+            AMOUNT,
+        )
+        .expect("Alice is the issuer");
+    });
+
+    assert_eq!(
+        transfer_from_eve_to_relay((Parent, AMOUNT).into()),
+        Err(pallet_xcm::Error::<Runtime>::LocalExecutionIncomplete.into())
+    );
+    network::deliver_upward_messages();
+
+    assert_eq!(relay_token_of_eve(), AMOUNT);
+    assert_eq!(eve_on_the_relay_chain(), relay_chain::INITIAL_BALANCE);
+}
@@ -0,0 +1,41 @@
+//! XCM messages weighed with the benchmarked weight of their instructions.
+
+use frame_support::weights::Weight;
+use parachain_template_runtime::{xcm_config::XcmWeigher, RuntimeCall, UNIT};
+use xcm::latest::prelude::*;
+use xcm_executor::traits::WeightBounds;
+
+/// A message from a sibling account that dispatches a call declared to weigh `call_weight`.
+fn transact(call_weight: Weight) -> Xcm<RuntimeCall> {
+    let fee: Asset = (Parent, UNIT).into();
+    Xcm(vec![
+        WithdrawAsset(fee.clone().into()),
+        BuyExecution {
+            fees: fee,
+            weight_limit: Unlimited,
+        },
+        Transact {
+            origin_kind: OriginKind::SovereignAccount,
+            require_weight_at_most: call_weight,
+            call: vec![].into(),
+        },
+    ])
+}
+
+#[test]
+fn transact_is_weighed_with_its_call() {
+    let call_weight = Weight::from_parts(2_000_000_000, 10_000);
+    let without_call = XcmWeigher::weight(&mut transact(Weight::zero())).expect("weighable");
+    let with_call = XcmWeigher::weight(&mut transact(call_weight)).expect("weighable");
+    assert_eq!(with_call, without_call + call_weight);
+}
+
+#[test]
+fn unsupported_instructions_are_never_affordable() {
+    let mut message = Xcm::<RuntimeCall>(vec![ExchangeAsset {
+        give: Wild(AllCounted(1)),
+        want: (Parent, UNIT).into(),
+        maximal: true,
+    }]);
+    assert_eq!(XcmWeigher::weight(&mut message), Ok(Weight::MAX));
+}
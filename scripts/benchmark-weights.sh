@@ -0,0 +1,66 @@
+#!/usr/bin/env bash
+# Benchmark all runtime pallets and both database backends and write the weights to
+# `runtime/src/weights/`.
+#
+# Run this on the reference machine, the weights are only as good as the hardware they were
+# measured on.
+
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+NODE=./target/release/parachain-template-node
+PALLETS=(
+	pallet_babe
+	pallet_grandpa
+	pallet_session
+	pallet_timestamp
+	pallet_message_queue
+	pallet_collator_selection
+	cumulus_pallet_parachain_system
+	cumulus_pallet_xcmp_queue
+	pallet_xcm
+)
+
+# With `relay-teleports`, the XCM benchmarks cover teleports and the checking account that tracks
+# them, so the weights hold for both transfer policies.
+cargo build --release --features runtime-benchmarks,relay-teleports
+
+for pallet in "${PALLETS[@]}"; do
+	echo "Benchmarking ${pallet}"
+	"${NODE}" benchmark pallet \
+		--chain=dev \
+		--wasm-execution=compiled \
+		--pallet="${pallet}" \
+		--extrinsic='*' \
+		--steps=50 \
+		--repeat=20 \
+		--output="./runtime/src/weights/${pallet}.rs"
+done
+
+# The XCM instruction weights are not a pallet's `WeightInfo`, the template writes them as plain
+# functions for `runtime/src/weights/xcm/mod.rs` to call.
+for pallet in fungible generic; do
+	echo "Benchmarking pallet_xcm_benchmarks::${pallet}"
+	"${NODE}" benchmark pallet \
+		--chain=dev \
+		--wasm-execution=compiled \
+		--pallet="pallet_xcm_benchmarks::${pallet}" \
+		--extrinsic='*' \
+		--steps=50 \
+		--repeat=20 \
+		--template=./scripts/xcm-bench-template.hbs \
+		--output="./runtime/src/weights/xcm/pallet_xcm_benchmarks_${pallet}.rs"
+done
+
+# The database weights need a node with a realistically sized state. Point `BASE_PATH` at a synced
+# node of the network, otherwise the weights of an empty dev database are measured.
+for db in rocksdb paritydb; do
+	echo "Benchmarking ${db} storage"
+	"${NODE}" benchmark storage \
+		--chain=dev \
+		--state-version=1 \
+		--database="${db}" \
+		${BASE_PATH:+--base-path="${BASE_PATH}"} \
+		--weight-path=./runtime/src/weights/
+done